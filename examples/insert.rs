@@ -9,8 +9,10 @@ fn main() -> Result<(), Error> {
     let username = env::var("SFDC_USERNAME").unwrap();
     let password = env::var("SFDC_PASSWORD").unwrap();
 
-    let mut client = Client::new(Some(client_id), Some(client_secret));
-    client.login_with_credential(username, password)?;
+    let client = Client::builder()
+        .client_id(&client_id)
+        .client_secret(&client_secret)
+        .login_with_credential(&username, &password)?;
 
     let nanos = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -6,6 +6,10 @@ fn main() -> Result<(), Error> {
     let password = env::var("SFDC_PASSWORD").unwrap();
 
     let mut client = Client::new(None, None);
-    client.login_by_soap(username, password)?;
+    let result = client.login_by_soap(username, password)?;
+    println!(
+        "Logged in as {} in org {}",
+        result.user_id, result.organization_id
+    );
     Ok(())
 }
@@ -0,0 +1,390 @@
+//! An asynchronous facade of [Client](crate::client::Client), for
+//! applications running on an async runtime such as tokio. The requests are
+//! not asynchronous: each call runs the blocking request on a bounded pool of
+//! threads, so that the executor is never blocked, and resolves once the
+//! response is received.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::client::Client;
+use crate::errors::Error;
+use crate::response::{QueryResponse, SearchResponse, UpsertResponse, UpsertResult};
+
+/// The number of threads of the pool of an [AsyncClient](AsyncClient) by
+/// default
+pub const DEFAULT_MAX_THREADS: usize = 8;
+
+/// A [Client](Client) with `async` methods. The client is configured and
+/// logged in beforehand, then shared by the calls. The calls wrap the
+/// blocking calls of the client: up to
+/// [DEFAULT_MAX_THREADS](DEFAULT_MAX_THREADS) of them run at once, on threads
+/// spawned as needed, and the others wait for a thread
+///
+/// ```rust,no_run
+/// use rust_sync_force::async_client::AsyncClient;
+/// use rust_sync_force::{Client, Error};
+/// use serde_json::Value;
+///
+/// async fn accounts(client: Client) -> Result<(), Error> {
+///     let client = AsyncClient::new(client);
+///     let r = client.query::<Value>("SELECT Id FROM Account").await?;
+///     println!("{} accounts", r.total_size);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct AsyncClient {
+    client: Arc<Client>,
+    pool: Arc<ThreadPool>,
+}
+
+impl AsyncClient {
+    pub fn new(client: Client) -> Self {
+        AsyncClient::with_max_threads(client, DEFAULT_MAX_THREADS)
+    }
+
+    /// Same as [new](AsyncClient::new), running up to `max_threads` calls at
+    /// once (at least one)
+    pub fn with_max_threads(client: Client, max_threads: usize) -> Self {
+        AsyncClient {
+            client: Arc::new(client),
+            pool: Arc::new(ThreadPool::new(max_threads.max(1))),
+        }
+    }
+
+    /// Returns the underlying client
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Runs any call of the [Client](Client) on the pool, without blocking
+    /// the executor. A call which panics resolves to an error
+    pub fn run<T, F>(&self, f: F) -> Blocking<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Client) -> Result<T, Error> + Send + 'static,
+    {
+        let client = self.client.clone();
+        Blocking::spawn(&self.pool, move || f(&client))
+    }
+
+    /// Same as [Client::query](Client::query)
+    pub async fn query<T: DeserializeOwned + Send + 'static>(
+        &self,
+        query: &str,
+    ) -> Result<QueryResponse<T>, Error> {
+        let query = query.to_string();
+        self.run(move |client| client.query(&query)).await
+    }
+
+    /// Same as [Client::query_all](Client::query_all)
+    pub async fn query_all<T: DeserializeOwned + Send + 'static>(
+        &self,
+        query: &str,
+    ) -> Result<QueryResponse<T>, Error> {
+        let query = query.to_string();
+        self.run(move |client| client.query_all(&query)).await
+    }
+
+    /// Same as [Client::search](Client::search)
+    pub async fn search(&self, query: &str) -> Result<SearchResponse, Error> {
+        let query = query.to_string();
+        self.run(move |client| client.search(&query)).await
+    }
+
+    /// Same as [Client::find_by_id](Client::find_by_id)
+    pub async fn find_by_id<T: DeserializeOwned + Send + 'static>(
+        &self,
+        sobject_type: &str,
+        id: &str,
+    ) -> Result<T, Error> {
+        let (sobject_type, id) = (sobject_type.to_string(), id.to_string());
+        self.run(move |client| client.find_by_id(&sobject_type, &id))
+            .await
+    }
+
+    /// Same as [Client::insert](Client::insert)
+    pub async fn insert<T: Serialize + Send + 'static>(
+        &self,
+        sobject_type: &str,
+        params: T,
+    ) -> Result<UpsertResponse, Error> {
+        let sobject_type = sobject_type.to_string();
+        self.run(move |client| client.insert(&sobject_type, params))
+            .await
+    }
+
+    /// Same as [Client::update](Client::update)
+    pub async fn update<T: Serialize + Send + 'static>(
+        &self,
+        sobject_type: &str,
+        id: &str,
+        params: T,
+    ) -> Result<(), Error> {
+        let (sobject_type, id) = (sobject_type.to_string(), id.to_string());
+        self.run(move |client| client.update(&sobject_type, &id, params))
+            .await
+    }
+
+    /// Same as [Client::upsert](Client::upsert)
+    pub async fn upsert<T: Serialize + Send + 'static>(
+        &self,
+        sobject_type: &str,
+        key_name: &str,
+        key: &str,
+        params: T,
+    ) -> Result<UpsertResult, Error> {
+        let (sobject_type, key_name, key) = (
+            sobject_type.to_string(),
+            key_name.to_string(),
+            key.to_string(),
+        );
+        self.run(move |client| client.upsert(&sobject_type, &key_name, &key, params))
+            .await
+    }
+
+    /// Same as [Client::delete](Client::delete)
+    pub async fn delete(&self, sobject_type: &str, id: &str) -> Result<(), Error> {
+        let (sobject_type, id) = (sobject_type.to_string(), id.to_string());
+        self.run(move |client| client.delete(&sobject_type, &id))
+            .await
+    }
+
+    /// Same as [Client::describe](Client::describe)
+    pub async fn describe(&self, sobject_type: &str) -> Result<String, Error> {
+        let sobject_type = sobject_type.to_string();
+        self.run(move |client| client.describe(&sobject_type)).await
+    }
+}
+
+impl From<Client> for AsyncClient {
+    fn from(client: Client) -> Self {
+        AsyncClient::new(client)
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The threads running the blocking calls of an [AsyncClient](AsyncClient).
+/// The threads stop once all the clones of the client are dropped
+struct ThreadPool {
+    sender: Sender<Job>,
+    receiver: Arc<Mutex<Receiver<Job>>>,
+    threads: AtomicUsize,
+    max_threads: usize,
+}
+
+impl ThreadPool {
+    fn new(max_threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        ThreadPool {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            threads: AtomicUsize::new(0),
+            max_threads,
+        }
+    }
+
+    /// Queues a job, spawning a thread unless the pool is full
+    fn execute(&self, job: Job) {
+        // The pool keeps the receiver, so that sending cannot fail
+        let _ = self.sender.send(job);
+        let spawned = self
+            .threads
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |threads| {
+                (threads < self.max_threads).then_some(threads + 1)
+            })
+            .is_ok();
+        if spawned {
+            let receiver = self.receiver.clone();
+            thread::spawn(move || loop {
+                let job = receiver.lock().unwrap().recv();
+                match job {
+                    Ok(job) => job(),
+                    Err(_) => return,
+                }
+            });
+        }
+    }
+}
+
+/// A blocking call queued on the pool of an [AsyncClient](AsyncClient),
+/// resolved with its result
+pub struct Blocking<T> {
+    state: Arc<Mutex<BlockingState<T>>>,
+}
+
+struct BlockingState<T> {
+    result: Option<Result<T, Error>>,
+    waker: Option<Waker>,
+}
+
+/// Resolves a [Blocking](Blocking) call, with an error when it is dropped
+/// without a result, e.g. when the pool is gone
+struct Completion<T> {
+    state: Arc<Mutex<BlockingState<T>>>,
+    result: Option<Result<T, Error>>,
+}
+
+impl<T> Completion<T> {
+    fn complete(mut self, result: Result<T, Error>) {
+        self.result = Some(result);
+    }
+}
+
+impl<T> Drop for Completion<T> {
+    fn drop(&mut self) {
+        let result = self.result.take().unwrap_or_else(|| {
+            Err(Error::GenericError(
+                "The blocking call was dropped without a result".to_string(),
+            ))
+        });
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: Send + 'static> Blocking<T> {
+    fn spawn(pool: &ThreadPool, f: impl FnOnce() -> Result<T, Error> + Send + 'static) -> Self {
+        let state = Arc::new(Mutex::new(BlockingState {
+            result: None,
+            waker: None,
+        }));
+        let completion = Completion {
+            state: state.clone(),
+            result: None,
+        };
+        pool.execute(Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+                Err(Error::GenericError(
+                    "The blocking call panicked".to_string(),
+                ))
+            });
+            completion.complete(result);
+        }));
+        Blocking { state }
+    }
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T, Error>> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncClient;
+    use crate::client::Client;
+    use crate::errors::Error;
+    use mockito::Server as MockServer;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::{self, Thread};
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Account {
+        name: String,
+    }
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn query_and_insert() -> Result<(), Error> {
+        let mut server = MockServer::new_with_port(0);
+        let _m = server
+            .mock("GET", "/services/data/v56.0/query/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "totalSize": 1, "done": true, "records": [{ "Name": "foo" }] }).to_string(),
+            )
+            .create();
+        let _m2 = server
+            .mock("POST", "/services/data/v56.0/sobjects/Account")
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "id": "12345", "success": true }).to_string())
+            .create();
+
+        let mut client = Client::new(None, None);
+        client.set_instance_url(&server.url());
+        client.set_access_token("this_is_access_token");
+        let client = AsyncClient::new(client);
+
+        let r = block_on(client.query::<Account>("SELECT Name FROM Account"))?;
+        assert_eq!("foo", r.records[0].name);
+        let r = block_on(client.insert("Account", json!({ "Name": "bar" })))?;
+        assert_eq!("12345", r.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn panic_and_pool_size() {
+        let client = AsyncClient::with_max_threads(Client::new(None, None), 2);
+        let r = block_on(client.run(|_| -> Result<(), Error> { panic!("oops") }));
+        assert!(matches!(r, Err(Error::GenericError(m)) if m.contains("panicked")));
+
+        let calls: Vec<_> = (0..6)
+            .map(|i| {
+                client.run(move |_| {
+                    thread::sleep(std::time::Duration::from_millis(20));
+                    Ok(i)
+                })
+            })
+            .collect();
+        let results: Vec<i32> = calls
+            .into_iter()
+            .map(|call| block_on(call).unwrap())
+            .collect();
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], results);
+        assert_eq!(2, client.pool.threads.load(Ordering::SeqCst));
+    }
+}
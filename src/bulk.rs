@@ -0,0 +1,676 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::Duration;
+
+use crate::client::Client;
+use crate::errors::Error;
+use crate::utils::parse_json;
+
+/// The state of a Bulk API 2.0 job
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub enum BulkJobState {
+    Open,
+    UploadComplete,
+    InProgress,
+    Aborted,
+    JobComplete,
+    Failed,
+}
+
+/// The information of a Bulk API 2.0 job
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkJobInfo {
+    pub id: String,
+    pub operation: String,
+    pub object: String,
+    pub state: BulkJobState,
+    pub error_message: Option<String>,
+    pub number_records_processed: Option<u64>,
+    pub number_records_failed: Option<u64>,
+}
+
+/// The operation of an ingest job
+#[derive(Clone, PartialEq, Debug)]
+pub enum BulkOperation {
+    Insert,
+    Update,
+    /// Upsert on the given external id field
+    Upsert(String),
+    Delete,
+    HardDelete,
+}
+
+impl BulkOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            BulkOperation::Insert => "insert",
+            BulkOperation::Update => "update",
+            BulkOperation::Upsert(_) => "upsert",
+            BulkOperation::Delete => "delete",
+            BulkOperation::HardDelete => "hardDelete",
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct QueryJobRequest<'a> {
+    operation: &'a str,
+    query: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct IngestJobRequest<'a> {
+    object: &'a str,
+    operation: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_id_field_name: Option<&'a str>,
+    content_type: &'a str,
+    line_ending: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct JobStateRequest<'a> {
+    state: &'a str,
+}
+
+/// Runs Bulk API 2.0 jobs, see [Client::bulk](Client::bulk)
+pub struct Bulk<'a> {
+    client: &'a Client,
+    poll_interval: Duration,
+}
+
+impl Client {
+    /// Returns a handle to run Bulk API 2.0 jobs, which are suited for large
+    /// data volumes
+    pub fn bulk(&self) -> Bulk<'_> {
+        Bulk {
+            client: self,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl<'a> Bulk<'a> {
+    /// Set how often the state of a job is checked while awaiting its
+    /// completion. Defaults to 5 seconds
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    fn jobs_path(&self, job_type: &str) -> String {
+        format!("/services/data/{}/jobs/{}", self.client.version, job_type)
+    }
+
+    /// Creates a query job
+    pub fn create_query_job(&self, soql: &str) -> Result<BulkJobInfo, Error> {
+        let res = self.client.sfdc_post(
+            self.jobs_path("query"),
+            QueryJobRequest {
+                operation: "query",
+                query: soql,
+            },
+        )?;
+        parse_json(res)
+    }
+
+    /// Returns the information of a query job
+    pub fn query_job(&self, job_id: &str) -> Result<BulkJobInfo, Error> {
+        let res = self
+            .client
+            .sfdc_get(format!("{}/{}", self.jobs_path("query"), job_id), None)?;
+        parse_json(res)
+    }
+
+    /// Creates an ingest job, to which the records are uploaded as CSV
+    pub fn create_ingest_job(
+        &self,
+        object: &str,
+        operation: &BulkOperation,
+    ) -> Result<BulkJobInfo, Error> {
+        let external_id_field_name = match operation {
+            BulkOperation::Upsert(external_id_field_name) => Some(external_id_field_name.as_str()),
+            _ => None,
+        };
+        let res = self.client.sfdc_post(
+            self.jobs_path("ingest"),
+            IngestJobRequest {
+                object,
+                operation: operation.name(),
+                external_id_field_name,
+                content_type: "CSV",
+                line_ending: "LF",
+            },
+        )?;
+        parse_json(res)
+    }
+
+    /// Uploads the CSV records of an ingest job. Records can only be uploaded
+    /// once per job
+    pub fn upload_ingest_data(&self, job_id: &str, csv: &[u8]) -> Result<(), Error> {
+        self.client.sfdc_send_bytes(
+            "PUT",
+            format!("{}/{}/batches", self.jobs_path("ingest"), job_id),
+            "text/csv",
+            csv,
+        )?;
+        Ok(())
+    }
+
+    /// Marks the upload of an ingest job as complete, so it gets processed
+    pub fn close_ingest_job(&self, job_id: &str) -> Result<BulkJobInfo, Error> {
+        let res = self.client.sfdc_patch(
+            format!("{}/{}", self.jobs_path("ingest"), job_id),
+            JobStateRequest {
+                state: "UploadComplete",
+            },
+        )?;
+        parse_json(res)
+    }
+
+    /// Returns the information of an ingest job
+    pub fn ingest_job(&self, job_id: &str) -> Result<BulkJobInfo, Error> {
+        let res = self
+            .client
+            .sfdc_get(format!("{}/{}", self.jobs_path("ingest"), job_id), None)?;
+        parse_json(res)
+    }
+
+    /// Runs an ingest job to completion with the given records, serialized to
+    /// CSV with one column per field. The `attributes` of the records are
+    /// skipped, `None` values are set to null (`#N/A`) and nested objects,
+    /// e.g. `{ "Account": { "ExtId__c": "1" } }`, become relationship columns
+    /// (`Account.ExtId__c`). The records which failed are counted in
+    /// `number_records_failed`
+    pub fn ingest_typed<T: Serialize>(
+        &self,
+        object: &str,
+        operation: BulkOperation,
+        records: &[T],
+    ) -> Result<BulkJobInfo, Error> {
+        let csv = records_to_csv(records)?;
+        let job = self.create_ingest_job(object, &operation)?;
+        self.upload_ingest_data(&job.id, &csv)?;
+        self.close_ingest_job(&job.id)?;
+        self.await_job(&job.id, |job_id| self.ingest_job(job_id))
+    }
+
+    /// Splits the records across `job_count` ingest jobs, which are run
+    /// concurrently as with [ingest_typed](Bulk::ingest_typed). The result of
+    /// each job is returned in the order of the records, so that the records
+    /// of the jobs which could not be run can be sent again
+    ///
+    /// # Errors
+    ///
+    /// `job_count` is zero.
+    pub fn ingest_parallel<T: Serialize + Sync>(
+        &self,
+        object: &str,
+        operation: BulkOperation,
+        records: &[T],
+        job_count: usize,
+    ) -> Result<Vec<Result<BulkJobInfo, Error>>, Error> {
+        if job_count == 0 {
+            return Err(Error::GenericError(
+                "At least one bulk job is required".to_string(),
+            ));
+        }
+        let chunk_size = records.len().div_ceil(job_count).max(1);
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = records
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let operation = operation.clone();
+                    scope.spawn(move || self.ingest_typed(object, operation, chunk))
+                })
+                .collect();
+            Ok(handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        Err(Error::GenericError("Bulk job thread panicked".to_string()))
+                    })
+                })
+                .collect())
+        })
+    }
+
+    /// Polls a job until it completes
+    ///
+    /// # Errors
+    ///
+    /// The job failed or was aborted.
+    fn await_job(
+        &self,
+        job_id: &str,
+        get_job: impl Fn(&str) -> Result<BulkJobInfo, Error>,
+    ) -> Result<BulkJobInfo, Error> {
+        loop {
+            let job = get_job(job_id)?;
+            match job.state {
+                BulkJobState::JobComplete => return Ok(job),
+                BulkJobState::Failed | BulkJobState::Aborted => {
+                    return Err(Error::GenericError(format!(
+                        "Bulk job {} {:?}: {}",
+                        job.id,
+                        job.state,
+                        job.error_message.unwrap_or_default()
+                    )))
+                }
+                _ => thread::sleep(self.poll_interval),
+            }
+        }
+    }
+
+    /// Runs a query job to completion and deserializes all the pages of its
+    /// CSV results. Empty fields are deserialized as `None`
+    pub fn query_typed<T: DeserializeOwned>(&self, soql: &str) -> Result<Vec<T>, Error> {
+        let job = self.create_query_job(soql)?;
+        self.await_job(&job.id, |job_id| self.query_job(job_id))?;
+
+        let results_path = format!("{}/{}/results", self.jobs_path("query"), job.id);
+        let mut records = vec![];
+        let mut locator: Option<String> = None;
+        loop {
+            let params = locator.as_deref().map(|locator| vec![("locator", locator)]);
+            let res = self.client.sfdc_get(results_path.clone(), params)?;
+            let next_locator = res
+                .header("Sforce-Locator")
+                .filter(|locator| !locator.is_empty() && *locator != "null")
+                .map(str::to_string);
+
+            for record in csv::Reader::from_reader(res.into_reader()).deserialize() {
+                records.push(record.map_err(|e| {
+                    Error::GenericError(format!("Could not deserialize bulk results: {}", e))
+                })?);
+            }
+
+            match next_locator {
+                Some(next_locator) => locator = Some(next_locator),
+                None => return Ok(records),
+            }
+        }
+    }
+}
+
+/// Serializes records to the CSV format of Bulk API 2.0 ingest jobs
+fn records_to_csv<T: Serialize>(records: &[T]) -> Result<Vec<u8>, Error> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        Error::GenericError(format!("Could not serialize bulk records: {}", e))
+    };
+
+    let mut rows = vec![];
+    for record in records {
+        let value = serde_json::to_value(record).map_err(|e| invalid(&e))?;
+        let mut row = BTreeMap::new();
+        match value {
+            Value::Object(fields) => {
+                for (name, value) in fields {
+                    if name != "attributes" {
+                        flatten_field(&mut row, name, value).map_err(|e| invalid(&e))?;
+                    }
+                }
+            }
+            _ => return Err(invalid(&"records must be structs or maps")),
+        }
+        rows.push(row);
+    }
+
+    let header: Vec<&String> = rows
+        .first()
+        .map(|row| row.keys().collect())
+        .unwrap_or_default();
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&header).map_err(|e| invalid(&e))?;
+    for row in rows.iter() {
+        if row.len() != header.len() || !header.iter().all(|name| row.contains_key(*name)) {
+            return Err(invalid(&"all records must have the same fields"));
+        }
+        writer.write_record(row.values()).map_err(|e| invalid(&e))?;
+    }
+    writer.into_inner().map_err(|e| invalid(&e))
+}
+
+fn flatten_field(
+    row: &mut BTreeMap<String, String>,
+    name: String,
+    value: Value,
+) -> Result<(), String> {
+    let value = match value {
+        Value::Null => "#N/A".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::String(value) => value,
+        Value::Object(fields) => {
+            for (field_name, value) in fields {
+                if field_name != "attributes" {
+                    flatten_field(row, format!("{}.{}", name, field_name), value)?;
+                }
+            }
+            return Ok(());
+        }
+        Value::Array(_) => return Err(format!("{} is an array", name)),
+    };
+    row.insert(name, value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::Error;
+    use crate::Client;
+    use mockito::Server as MockServer;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    struct Account {
+        id: String,
+        name: String,
+        number_of_employees: Option<u32>,
+    }
+
+    fn create_test_client(server: &MockServer) -> Client {
+        let mut client = Client::new(None, None);
+        client.set_instance_url(&server.url());
+        client.set_access_token("this_is_access_token");
+        client
+    }
+
+    #[test]
+    fn query_typed() -> Result<(), Error> {
+        let mut server = MockServer::new_with_port(0);
+        let _m = server
+            .mock("POST", "/services/data/v56.0/jobs/query")
+            .match_body(mockito::Matcher::Json(json!({
+                "operation": "query",
+                "query": "SELECT Id, Name, NumberOfEmployees FROM Account",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "750xx",
+                    "operation": "query",
+                    "object": "Account",
+                    "state": "UploadComplete",
+                })
+                .to_string(),
+            )
+            .create();
+        let _m = server
+            .mock("GET", "/services/data/v56.0/jobs/query/750xx")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "750xx",
+                    "operation": "query",
+                    "object": "Account",
+                    "state": "JobComplete",
+                    "numberRecordsProcessed": 2,
+                })
+                .to_string(),
+            )
+            .create();
+        let _m = server
+            .mock("GET", "/services/data/v56.0/jobs/query/750xx/results")
+            .match_query(mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "text/csv")
+            .with_header("Sforce-Locator", "MTAwMDA")
+            .with_body("\"Id\",\"Name\",\"NumberOfEmployees\"\n\"001xx1\",\"foo\",\"10\"\n")
+            .create();
+        let _m = server
+            .mock("GET", "/services/data/v56.0/jobs/query/750xx/results")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "locator".into(),
+                "MTAwMDA".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "text/csv")
+            .with_header("Sforce-Locator", "null")
+            .with_body("\"Id\",\"Name\",\"NumberOfEmployees\"\n\"001xx2\",\"bar\",\"\"\n")
+            .create();
+
+        let client = create_test_client(&server);
+        let r: Vec<Account> = client
+            .bulk()
+            .with_poll_interval(Duration::ZERO)
+            .query_typed("SELECT Id, Name, NumberOfEmployees FROM Account")?;
+        assert_eq!(2, r.len());
+        assert_eq!("001xx1", r[0].id);
+        assert_eq!(Some(10), r[0].number_of_employees);
+        assert_eq!("bar", r[1].name);
+        assert_eq!(None, r[1].number_of_employees);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ingest_typed() -> Result<(), Error> {
+        use super::{BulkJobState, BulkOperation};
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Contact {
+            last_name: String,
+            do_not_call: bool,
+            birthdate: Option<String>,
+            account: AccountRef,
+        }
+
+        #[derive(Serialize)]
+        struct AccountRef {
+            #[serde(rename = "ExtId__c")]
+            ext_id: String,
+        }
+
+        let mut server = MockServer::new_with_port(0);
+        let _m = server
+            .mock("POST", "/services/data/v56.0/jobs/ingest")
+            .match_body(mockito::Matcher::Json(json!({
+                "object": "Contact",
+                "operation": "upsert",
+                "externalIdFieldName": "ExtId__c",
+                "contentType": "CSV",
+                "lineEnding": "LF",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "id": "750xx", "operation": "upsert", "object": "Contact", "state": "Open" })
+                    .to_string(),
+            )
+            .create();
+        let upload_mock = server
+            .mock("PUT", "/services/data/v56.0/jobs/ingest/750xx/batches")
+            .match_header("content-type", "text/csv")
+            .match_body(
+                "Account.ExtId__c,Birthdate,DoNotCall,LastName\n\
+                 A1,1990-01-01,true,\"Doe, Jr\"\n\
+                 A2,#N/A,false,Roe\n",
+            )
+            .with_status(201)
+            .create();
+        let close_mock = server
+            .mock("PATCH", "/services/data/v56.0/jobs/ingest/750xx")
+            .match_body(mockito::Matcher::Json(json!({ "state": "UploadComplete" })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "id": "750xx", "operation": "upsert", "object": "Contact", "state": "UploadComplete" })
+                    .to_string(),
+            )
+            .create();
+        let _m = server
+            .mock("GET", "/services/data/v56.0/jobs/ingest/750xx")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "750xx",
+                    "operation": "upsert",
+                    "object": "Contact",
+                    "state": "JobComplete",
+                    "numberRecordsProcessed": 2,
+                    "numberRecordsFailed": 0,
+                })
+                .to_string(),
+            )
+            .create();
+
+        let records = vec![
+            Contact {
+                last_name: "Doe, Jr".to_string(),
+                do_not_call: true,
+                birthdate: Some("1990-01-01".to_string()),
+                account: AccountRef {
+                    ext_id: "A1".to_string(),
+                },
+            },
+            Contact {
+                last_name: "Roe".to_string(),
+                do_not_call: false,
+                birthdate: None,
+                account: AccountRef {
+                    ext_id: "A2".to_string(),
+                },
+            },
+        ];
+
+        let client = create_test_client(&server);
+        let r = client
+            .bulk()
+            .with_poll_interval(Duration::ZERO)
+            .ingest_typed(
+                "Contact",
+                BulkOperation::Upsert("ExtId__c".to_string()),
+                &records,
+            )?;
+        upload_mock.assert();
+        close_mock.assert();
+        assert_eq!(BulkJobState::JobComplete, r.state);
+        assert_eq!(Some(2), r.number_records_processed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ingest_parallel() -> Result<(), Error> {
+        use super::BulkOperation;
+
+        let mut server = MockServer::new_with_port(0);
+        let create_mock = server
+            .mock("POST", "/services/data/v56.0/jobs/ingest")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "id": "750xx", "operation": "insert", "object": "Account", "state": "Open" })
+                    .to_string(),
+            )
+            .expect(2)
+            .create();
+        let upload_mock = server
+            .mock("PUT", "/services/data/v56.0/jobs/ingest/750xx/batches")
+            .match_body(mockito::Matcher::Regex("^Name\na\nb\n$".to_string()))
+            .with_status(201)
+            .expect(1)
+            .create();
+        let failed_upload_mock = server
+            .mock("PUT", "/services/data/v56.0/jobs/ingest/750xx/batches")
+            .match_body(mockito::Matcher::Regex("^Name\nc\n$".to_string()))
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(json!([{ "message": "bad", "errorCode": "INVALIDDATA" }]).to_string())
+            .expect(1)
+            .create();
+        let _m = server
+            .mock("PATCH", "/services/data/v56.0/jobs/ingest/750xx")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "id": "750xx", "operation": "insert", "object": "Account", "state": "UploadComplete" })
+                    .to_string(),
+            )
+            .create();
+        let _m = server
+            .mock("GET", "/services/data/v56.0/jobs/ingest/750xx")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "id": "750xx", "operation": "insert", "object": "Account", "state": "JobComplete" })
+                    .to_string(),
+            )
+            .create();
+
+        let records = vec![
+            json!({ "Name": "a" }),
+            json!({ "Name": "b" }),
+            json!({ "Name": "c" }),
+        ];
+        let client = create_test_client(&server);
+        let bulk = client.bulk().with_poll_interval(Duration::ZERO);
+        let r = bulk.ingest_parallel("Account", BulkOperation::Insert, &records, 2)?;
+        assert_eq!(2, r.len());
+        assert!(r[0].is_ok());
+        assert!(r[1]
+            .as_ref()
+            .is_err_and(|e| e.to_string().contains("INVALIDDATA")));
+        create_mock.assert();
+        upload_mock.assert();
+        failed_upload_mock.assert();
+        assert!(bulk
+            .ingest_parallel("Account", BulkOperation::Insert, &records, 0)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_typed_failed_job() {
+        let mut server = MockServer::new_with_port(0);
+        let _m = server
+            .mock("POST", "/services/data/v56.0/jobs/query")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "id": "750xx", "operation": "query", "object": "Account", "state": "UploadComplete" })
+                    .to_string(),
+            )
+            .create();
+        let _m = server
+            .mock("GET", "/services/data/v56.0/jobs/query/750xx")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "750xx",
+                    "operation": "query",
+                    "object": "Account",
+                    "state": "Failed",
+                    "errorMessage": "INVALID_FIELD",
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = create_test_client(&server);
+        let r = client
+            .bulk()
+            .query_typed::<Account>("SELECT Foo FROM Account");
+        assert!(
+            matches!(r, Err(Error::GenericError(message)) if message.contains("INVALID_FIELD"))
+        );
+    }
+}
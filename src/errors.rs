@@ -1,6 +1,9 @@
 use serde_json::Value;
 
-use crate::response::ErrorResponse;
+use crate::response::{DuplicateResult, ErrorResponse};
+
+/// Maximum length of the body kept in [SfdcError](Error::SfdcError)
+pub const ERROR_BODY_MAX_LEN: usize = 4096;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -13,44 +16,395 @@ pub enum Error {
         url: String,
         sfdc_errors: Option<Vec<ErrorResponse>>,
         transport_error: Option<String>,
+        /// The body of the response when it is not JSON, e.g. the HTML page
+        /// of a gateway, truncated to [ERROR_BODY_MAX_LEN](ERROR_BODY_MAX_LEN)
+        /// bytes
+        body: Option<String>,
     },
 
     #[error("Error: {0}")]
     GenericError(String),
 
+    #[error("replay id {replay_id} of {channel} is no longer retained")]
+    ReplayIdExpired { channel: String, replay_id: i64 },
+
+    #[error("invalid connected app credentials: {0}")]
+    InvalidClientCredentials(String),
+
+    #[error("invalid user credentials: {0}")]
+    InvalidUserCredentials(String),
+
+    #[error("missing credentials: {0} is not set")]
+    MissingCredentials(&'static str),
+
+    #[error("no {sobject_type} found with {key_name} {key}")]
+    NotFound {
+        sobject_type: String,
+        key_name: String,
+        key: String,
+    },
+
+    #[error("{} {sobject_type} records found with {key_name} {key}", .urls.len())]
+    MultipleRecords {
+        sobject_type: String,
+        key_name: String,
+        key: String,
+        urls: Vec<String>,
+    },
+
+    #[error("failed after {attempts} attempts: {source}")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<Error>,
+    },
+
+    #[error("API usage {used}/{limit} reached the threshold of the client")]
+    ApiLimitNearlyExhausted { used: u64, limit: u64 },
+
+    #[error("{method} {url} rejected, the client is read-only")]
+    ReadOnly { method: String, url: String },
+
+    #[error("rate limit of the client reached, retry after {retry_after:?}")]
+    Throttled { retry_after: std::time::Duration },
+
+    #[error("could not parse the response: {source}, body: {body}")]
+    DeserializeError {
+        /// The body of the response, truncated to
+        /// [ERROR_BODY_MAX_LEN](ERROR_BODY_MAX_LEN) bytes
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
     #[error("Input Output Error {0}")]
     IOError(#[from] ::std::io::Error),
 }
 
+impl Error {
+    /// The code of the first error returned by Salesforce, e.g.
+    /// `DUPLICATE_VALUE` for a duplicate external id
+    ///
+    /// ```rust
+    /// use rust_sync_force::errors::{Error, SalesforceErrorCode};
+    ///
+    /// fn is_duplicate(error: &Error) -> bool {
+    ///     matches!(
+    ///         error.error_code(),
+    ///         Some(SalesforceErrorCode::DuplicateValue | SalesforceErrorCode::DuplicatesDetected)
+    ///     )
+    /// }
+    /// ```
+    pub fn error_code(&self) -> Option<SalesforceErrorCode> {
+        self.error_codes().into_iter().next()
+    }
+
+    /// The details of the duplicate rule which rejected a record, with the
+    /// ids of the matching records
+    pub fn duplicate_result(&self) -> Option<&DuplicateResult> {
+        match self {
+            Error::SfdcError {
+                sfdc_errors: Some(sfdc_errors),
+                ..
+            } => sfdc_errors
+                .iter()
+                .find_map(|sfdc_error| sfdc_error.duplicate_result.as_ref()),
+            Error::RetriesExhausted { source, .. } => source.duplicate_result(),
+            _ => None,
+        }
+    }
+
+    /// The codes of all the errors returned by Salesforce
+    pub fn error_codes(&self) -> Vec<SalesforceErrorCode> {
+        match self {
+            Error::SfdcError {
+                sfdc_errors: Some(sfdc_errors),
+                ..
+            } => sfdc_errors
+                .iter()
+                .flat_map(|sfdc_error| {
+                    if sfdc_error.error_code.trim().is_empty() {
+                        // The body of the response is kept as is in the message
+                        codes_from_body(&sfdc_error.message)
+                    } else {
+                        vec![SalesforceErrorCode::from(sfdc_error.error_code.as_str())]
+                    }
+                })
+                .collect(),
+            Error::RetriesExhausted { source, .. } => source.error_codes(),
+            _ => vec![],
+        }
+    }
+
+    /// Tells whether Salesforce could not be reached, e.g. the network is
+    /// unreachable or the connection timed out, as opposed to Salesforce
+    /// rejecting the request
+    pub fn is_transport_error(&self) -> bool {
+        match self {
+            Error::SfdcError {
+                transport_error, ..
+            } => transport_error.is_some(),
+            Error::RetriesExhausted { source, .. } => source.is_transport_error(),
+            _ => false,
+        }
+    }
+}
+
+fn duplicate_result_from_body(body: &Value) -> Option<DuplicateResult> {
+    body.as_array()?
+        .iter()
+        .find_map(|error| error.get("duplicateResult"))
+        .and_then(|duplicate_result| serde_json::from_value(duplicate_result.clone()).ok())
+}
+
+fn codes_from_body(body: &Value) -> Vec<SalesforceErrorCode> {
+    let errors = match body {
+        Value::Array(errors) => errors.iter().collect(),
+        error => vec![error],
+    };
+    errors
+        .into_iter()
+        .filter_map(|error| error.get("errorCode")?.as_str())
+        .map(SalesforceErrorCode::from)
+        .collect()
+}
+
+/// The common error codes returned by Salesforce, see
+/// [StatusCode](https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_concepts_core_data_objects.htm#statuscode)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SalesforceErrorCode {
+    CannotInsertUpdateActivateEntity,
+    DuplicateValue,
+    DuplicatesDetected,
+    EntityIsDeleted,
+    FieldCustomValidationException,
+    FieldIntegrityException,
+    InsufficientAccessOnCrossReferenceEntity,
+    InsufficientAccessOrReadonly,
+    InvalidCrossReferenceKey,
+    InvalidField,
+    InvalidFieldForInsertUpdate,
+    InvalidSessionId,
+    InvalidType,
+    MalformedId,
+    MalformedQuery,
+    NotFound,
+    RequestLimitExceeded,
+    RequiredFieldMissing,
+    StringTooLong,
+    UnableToLockRow,
+    Unknown(String),
+}
+
+impl SalesforceErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SalesforceErrorCode::CannotInsertUpdateActivateEntity => {
+                "CANNOT_INSERT_UPDATE_ACTIVATE_ENTITY"
+            }
+            SalesforceErrorCode::DuplicateValue => "DUPLICATE_VALUE",
+            SalesforceErrorCode::DuplicatesDetected => "DUPLICATES_DETECTED",
+            SalesforceErrorCode::EntityIsDeleted => "ENTITY_IS_DELETED",
+            SalesforceErrorCode::FieldCustomValidationException => {
+                "FIELD_CUSTOM_VALIDATION_EXCEPTION"
+            }
+            SalesforceErrorCode::FieldIntegrityException => "FIELD_INTEGRITY_EXCEPTION",
+            SalesforceErrorCode::InsufficientAccessOnCrossReferenceEntity => {
+                "INSUFFICIENT_ACCESS_ON_CROSS_REFERENCE_ENTITY"
+            }
+            SalesforceErrorCode::InsufficientAccessOrReadonly => "INSUFFICIENT_ACCESS_OR_READONLY",
+            SalesforceErrorCode::InvalidCrossReferenceKey => "INVALID_CROSS_REFERENCE_KEY",
+            SalesforceErrorCode::InvalidField => "INVALID_FIELD",
+            SalesforceErrorCode::InvalidFieldForInsertUpdate => "INVALID_FIELD_FOR_INSERT_UPDATE",
+            SalesforceErrorCode::InvalidSessionId => "INVALID_SESSION_ID",
+            SalesforceErrorCode::InvalidType => "INVALID_TYPE",
+            SalesforceErrorCode::MalformedId => "MALFORMED_ID",
+            SalesforceErrorCode::MalformedQuery => "MALFORMED_QUERY",
+            SalesforceErrorCode::NotFound => "NOT_FOUND",
+            SalesforceErrorCode::RequestLimitExceeded => "REQUEST_LIMIT_EXCEEDED",
+            SalesforceErrorCode::RequiredFieldMissing => "REQUIRED_FIELD_MISSING",
+            SalesforceErrorCode::StringTooLong => "STRING_TOO_LONG",
+            SalesforceErrorCode::UnableToLockRow => "UNABLE_TO_LOCK_ROW",
+            SalesforceErrorCode::Unknown(code) => code,
+        }
+    }
+
+    /// Tells whether a record was rejected by a duplicate rule or a unique
+    /// field
+    pub fn is_duplicate(&self) -> bool {
+        matches!(
+            self,
+            SalesforceErrorCode::DuplicateValue | SalesforceErrorCode::DuplicatesDetected
+        )
+    }
+
+    /// Tells whether a record was rejected by a validation rule or a
+    /// constraint of its fields
+    pub fn is_validation(&self) -> bool {
+        matches!(
+            self,
+            SalesforceErrorCode::FieldCustomValidationException
+                | SalesforceErrorCode::FieldIntegrityException
+                | SalesforceErrorCode::RequiredFieldMissing
+                | SalesforceErrorCode::StringTooLong
+        )
+    }
+}
+
+impl From<&str> for SalesforceErrorCode {
+    fn from(code: &str) -> Self {
+        let code = code.trim().to_ascii_uppercase();
+        match code.as_str() {
+            "CANNOT_INSERT_UPDATE_ACTIVATE_ENTITY" => {
+                SalesforceErrorCode::CannotInsertUpdateActivateEntity
+            }
+            "DUPLICATE_VALUE" => SalesforceErrorCode::DuplicateValue,
+            "DUPLICATES_DETECTED" => SalesforceErrorCode::DuplicatesDetected,
+            "ENTITY_IS_DELETED" => SalesforceErrorCode::EntityIsDeleted,
+            "FIELD_CUSTOM_VALIDATION_EXCEPTION" => {
+                SalesforceErrorCode::FieldCustomValidationException
+            }
+            "FIELD_INTEGRITY_EXCEPTION" => SalesforceErrorCode::FieldIntegrityException,
+            "INSUFFICIENT_ACCESS_ON_CROSS_REFERENCE_ENTITY" => {
+                SalesforceErrorCode::InsufficientAccessOnCrossReferenceEntity
+            }
+            "INSUFFICIENT_ACCESS_OR_READONLY" => SalesforceErrorCode::InsufficientAccessOrReadonly,
+            "INVALID_CROSS_REFERENCE_KEY" => SalesforceErrorCode::InvalidCrossReferenceKey,
+            "INVALID_FIELD" => SalesforceErrorCode::InvalidField,
+            "INVALID_FIELD_FOR_INSERT_UPDATE" => SalesforceErrorCode::InvalidFieldForInsertUpdate,
+            "INVALID_SESSION_ID" => SalesforceErrorCode::InvalidSessionId,
+            "INVALID_TYPE" => SalesforceErrorCode::InvalidType,
+            "MALFORMED_ID" => SalesforceErrorCode::MalformedId,
+            "MALFORMED_QUERY" => SalesforceErrorCode::MalformedQuery,
+            "NOT_FOUND" => SalesforceErrorCode::NotFound,
+            "REQUEST_LIMIT_EXCEEDED" => SalesforceErrorCode::RequestLimitExceeded,
+            "REQUIRED_FIELD_MISSING" => SalesforceErrorCode::RequiredFieldMissing,
+            "STRING_TOO_LONG" => SalesforceErrorCode::StringTooLong,
+            "UNABLE_TO_LOCK_ROW" => SalesforceErrorCode::UnableToLockRow,
+            _ => SalesforceErrorCode::Unknown(code),
+        }
+    }
+}
+
+impl std::fmt::Display for SalesforceErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl From<ureq::Error> for Error {
     fn from(e: ureq::Error) -> Self {
         match e {
             ureq::Error::Status(status, response) => {
                 let url = response.get_url().to_string();
                 let response_string = format!("{:?}", response);
-                let message = if let Ok(response_value) = response.into_json::<Value>() {
-                    response_value
-                } else {
-                    Value::String(response_string)
+                let body = response.into_string().unwrap_or_default();
+                let (message, body) = match serde_json::from_str::<Value>(&body) {
+                    Ok(response_value) => (response_value, None),
+                    Err(_) => (
+                        Value::String(response_string),
+                        Some(truncate(body)).filter(|body| !body.is_empty()),
+                    ),
                 };
+                let duplicate_result = duplicate_result_from_body(&message);
                 let error_response = ErrorResponse {
                     message,
                     error_code: "".to_string(),
                     fields: None,
+                    duplicate_result,
                 };
                 return Error::SfdcError {
                     status,
                     url,
                     sfdc_errors: Some(vec![error_response]),
                     transport_error: None,
+                    body,
                 };
             }
             ureq::Error::Transport(transport) => Error::SfdcError {
                 status: 0,
-                url: transport.url().unwrap().to_string(),
+                url: transport
+                    .url()
+                    .map(|url| url.to_string())
+                    .unwrap_or_default(),
                 sfdc_errors: None,
                 transport_error: Some(transport.to_string()),
+                body: None,
             },
         }
     }
 }
+
+/// Truncates a body to [ERROR_BODY_MAX_LEN](ERROR_BODY_MAX_LEN) bytes, on a
+/// character boundary
+pub(crate) fn truncate(mut body: String) -> String {
+    if body.len() > ERROR_BODY_MAX_LEN {
+        let end = (0..=ERROR_BODY_MAX_LEN)
+            .rev()
+            .find(|&i| body.is_char_boundary(i))
+            .unwrap_or(0);
+        body.truncate(end);
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{truncate, Error, SalesforceErrorCode, ERROR_BODY_MAX_LEN};
+    use crate::response::ErrorResponse;
+    use serde_json::json;
+
+    #[test]
+    fn parses_error_codes() {
+        assert_eq!(
+            SalesforceErrorCode::DuplicateValue,
+            SalesforceErrorCode::from(" duplicate_value\n")
+        );
+        assert_eq!(
+            SalesforceErrorCode::Unknown("SOME_NEW_CODE".to_string()),
+            SalesforceErrorCode::from("some_new_code")
+        );
+        assert!(SalesforceErrorCode::DuplicatesDetected.is_duplicate());
+        assert!(SalesforceErrorCode::FieldCustomValidationException.is_validation());
+    }
+
+    #[test]
+    fn error_codes_from_body() {
+        let error = Error::SfdcError {
+            status: 400,
+            url: "".to_string(),
+            sfdc_errors: Some(vec![ErrorResponse {
+                message: json!([
+                    { "message": "duplicate value found", "errorCode": "DUPLICATE_VALUE" },
+                    { "message": "invalid", "errorCode": "FIELD_CUSTOM_VALIDATION_EXCEPTION" },
+                ]),
+                error_code: "".to_string(),
+                fields: None,
+                duplicate_result: None,
+            }]),
+            transport_error: None,
+            body: None,
+        };
+        assert_eq!(
+            Some(SalesforceErrorCode::DuplicateValue),
+            error.error_code()
+        );
+        assert_eq!(
+            vec![
+                SalesforceErrorCode::DuplicateValue,
+                SalesforceErrorCode::FieldCustomValidationException
+            ],
+            error.error_codes()
+        );
+        assert_eq!(None, Error::NotLoggedIn.error_code());
+    }
+
+    #[test]
+    fn truncates_body() {
+        assert_eq!("<html>", truncate("<html>".to_string()));
+        let body = truncate("é".repeat(ERROR_BODY_MAX_LEN));
+        assert_eq!(ERROR_BODY_MAX_LEN, body.len());
+        let body = truncate(format!("a{}", "é".repeat(ERROR_BODY_MAX_LEN)));
+        assert_eq!(ERROR_BODY_MAX_LEN - 1, body.len());
+    }
+}
@@ -133,11 +133,32 @@
 extern crate thiserror;
 extern crate ureq;
 
+#[cfg(feature = "async")]
+pub mod async_client;
+pub mod bulk;
 pub mod client;
 pub mod errors;
+#[cfg(feature = "jwt")]
+pub mod jwt;
+#[cfg(feature = "mtls")]
+mod mtls;
+pub mod observer;
+pub mod pool;
+mod rate_limit;
 pub mod response;
+pub mod sobject;
+pub mod stats;
 pub mod stream;
+#[cfg(test)]
+mod test_utils;
+#[cfg(feature = "tls")]
+mod tls;
+pub mod transport;
 pub mod utils;
 
 pub type Client = client::Client;
+pub type ClientBuilder = client::ClientBuilder;
+pub type LoginEndpoint = client::LoginEndpoint;
+pub type RetryPolicy = client::RetryPolicy;
+pub type WriteOptions = client::WriteOptions;
 pub type Error = errors::Error;
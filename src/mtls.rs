@@ -0,0 +1,115 @@
+use ring::signature::{
+    EcdsaKeyPair, KeyPair, RsaKeyPair, ECDSA_P256_SHA256_ASN1_SIGNING,
+    ECDSA_P384_SHA384_ASN1_SIGNING,
+};
+use rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore};
+use rustls_pemfile::Item;
+use std::sync::Arc;
+
+use crate::errors::Error;
+
+/// The port on which Salesforce requires the client certificate
+pub(crate) const MTLS_PORT: u16 = 8443;
+
+/// Builds the TLS configuration presenting the client certificate and
+/// trusting `roots`. The certificate chain and the private key are checked to
+/// belong together, so that a mismatch is reported before the first request
+pub(crate) fn tls_config(
+    cert_pem: &str,
+    key_pem: &str,
+    roots: RootCertStore,
+) -> Result<Arc<ClientConfig>, Error> {
+    let invalid =
+        |reason: &str| Error::GenericError(format!("Invalid client certificate: {}", reason));
+
+    let certs: Vec<Certificate> = read_pem(cert_pem)?
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::X509Certificate(der) => Some(Certificate(der)),
+            _ => None,
+        })
+        .collect();
+    let cert = certs
+        .first()
+        .ok_or_else(|| invalid("no certificate found"))?;
+
+    let key = read_pem(key_pem)?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::RSAKey(der) | Item::PKCS8Key(der) | Item::ECKey(der) => Some(der),
+            _ => None,
+        })
+        .ok_or_else(|| invalid("no private key found"))?;
+    if let Some(public_key) = public_key(&key) {
+        if !contains(&cert.0, &public_key) {
+            return Err(invalid("the private key does not match the certificate"));
+        }
+    }
+
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_single_cert(certs, PrivateKey(key))
+        .map_err(|e| invalid(&e.to_string()))?;
+    Ok(Arc::new(config))
+}
+
+fn read_pem(pem: &str) -> Result<Vec<Item>, Error> {
+    rustls_pemfile::read_all(&mut pem.as_bytes())
+        .map_err(|e| Error::GenericError(format!("Invalid client certificate: {}", e)))
+}
+
+/// Derives the public key of an RSA or PKCS#8 ECDSA private key, as encoded
+/// in the certificate. `None` when the key format is not supported by ring
+fn public_key(key: &[u8]) -> Option<Vec<u8>> {
+    if let Ok(key_pair) = RsaKeyPair::from_der(key).or_else(|_| RsaKeyPair::from_pkcs8(key)) {
+        return Some(key_pair.public_key().as_ref().to_vec());
+    }
+    [
+        &ECDSA_P256_SHA256_ASN1_SIGNING,
+        &ECDSA_P384_SHA384_ASN1_SIGNING,
+    ]
+    .into_iter()
+    .find_map(|alg| EcdsaKeyPair::from_pkcs8(alg, key).ok())
+    .map(|key_pair| key_pair.public_key().as_ref().to_vec())
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tls_config as mtls_config;
+    use crate::errors::Error;
+    use crate::tls::root_store;
+    use rustls::ClientConfig;
+    use std::fs;
+    use std::sync::Arc;
+
+    fn tls_config(cert_pem: &str, key_pem: &str) -> Result<Arc<ClientConfig>, Error> {
+        mtls_config(cert_pem, key_pem, root_store(&[])?)
+    }
+
+    #[test]
+    fn accepts_matching_key() {
+        let cert = fs::read_to_string("tests/fixtures/jwt_cert.pem").unwrap();
+        let pkcs1 = fs::read_to_string("tests/fixtures/jwt_pkcs1.pem").unwrap();
+        let pkcs8 = fs::read_to_string("tests/fixtures/jwt_pkcs8.pem").unwrap();
+        assert!(tls_config(&cert, &pkcs1).is_ok());
+        assert!(tls_config(&cert, &pkcs8).is_ok());
+    }
+
+    #[test]
+    fn rejects_mismatched_key() {
+        let cert = fs::read_to_string("tests/fixtures/jwt_cert.pem").unwrap();
+        let key = fs::read_to_string("tests/fixtures/mtls_ec_pkcs8.pem").unwrap();
+        let err = tls_config(&cert, &key).err().unwrap();
+        assert!(err.to_string().contains("does not match"));
+
+        let err = tls_config(&key, &key).err().unwrap();
+        assert!(err.to_string().contains("no certificate found"));
+    }
+}
@@ -0,0 +1,82 @@
+//! Hook called around every request of a [Client](crate::client::Client),
+//! see [set_observer](crate::client::Client::set_observer), e.g. to record
+//! the calls in a metrics pipeline
+
+use std::time::Duration;
+
+/// A request about to be sent. The `Authorization` header is left out
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestInfo {
+    pub method: String,
+    /// The URL, including the query parameters
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RequestInfo {
+    pub(crate) fn from_request(req: &ureq::Request) -> Self {
+        RequestInfo {
+            method: req.method().to_string(),
+            url: req
+                .request_url()
+                .map(|url| url.as_url().to_string())
+                .unwrap_or_else(|_| req.url().to_string()),
+            headers: req
+                .header_names()
+                .into_iter()
+                .filter(|name| !name.eq_ignore_ascii_case("authorization"))
+                .filter_map(|name| {
+                    let value = req.header(&name)?.to_string();
+                    Some((name, value))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Why a request failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Salesforce could not be reached, e.g. the connection timed out
+    Transport,
+    /// 401, the session expired or was revoked
+    Unauthorized,
+    /// 429, too many requests
+    Throttled,
+    /// Any other status from 400 to 499
+    Client,
+    /// A status from 500
+    Server,
+}
+
+impl ErrorClass {
+    pub(crate) fn from_error(error: &ureq::Error) -> Self {
+        match error {
+            ureq::Error::Transport(_) => ErrorClass::Transport,
+            ureq::Error::Status(401, _) => ErrorClass::Unauthorized,
+            ureq::Error::Status(429, _) => ErrorClass::Throttled,
+            ureq::Error::Status(status, _) if *status >= 500 => ErrorClass::Server,
+            ureq::Error::Status(_, _) => ErrorClass::Client,
+        }
+    }
+}
+
+/// The outcome of a request
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResponseInfo {
+    /// The status of the response, `None` when Salesforce could not be
+    /// reached
+    pub status: Option<u16>,
+    pub elapsed: Duration,
+    pub error: Option<ErrorClass>,
+}
+
+/// Receives the requests of a [Client](crate::client::Client). Every attempt
+/// is reported, i.e. a request sent again after refreshing the session or
+/// by the [retry policy](crate::client::Client::set_retry_policy) is
+/// reported twice, and each page of a query is a request of its own
+pub trait RequestObserver: Send + Sync {
+    fn on_request(&self, _request: &RequestInfo) {}
+
+    fn on_response(&self, request: &RequestInfo, response: &ResponseInfo);
+}
@@ -0,0 +1,263 @@
+use crate::client::Client;
+use crate::errors::Error;
+#[cfg(feature = "jwt")]
+use crate::jwt::JwtCredentials;
+
+use std::collections::HashMap;
+#[cfg(feature = "jwt")]
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// How an org of a [ClientPool](ClientPool) logs in
+#[derive(Clone, Debug)]
+pub enum OrgCredentials {
+    /// Username Password Flow
+    Password { username: String, password: String },
+    /// Refresh Token Flow
+    RefreshToken(String),
+    /// JWT Bearer Flow. The private key is read on every login, so that keys
+    /// rotated on disk are picked up
+    #[cfg(feature = "jwt")]
+    Jwt {
+        username: String,
+        private_key_file: PathBuf,
+    },
+}
+
+/// The stored credentials and settings of an org of a [ClientPool](ClientPool)
+#[derive(Clone, Debug)]
+pub struct OrgConfig {
+    client_id: String,
+    client_secret: Option<String>,
+    credentials: OrgCredentials,
+    login_endpoint: Option<String>,
+    version: Option<String>,
+}
+
+impl OrgConfig {
+    pub fn new(client_id: &str, client_secret: Option<&str>, credentials: OrgCredentials) -> Self {
+        OrgConfig {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.map(str::to_string),
+            credentials,
+            login_endpoint: None,
+            version: None,
+        }
+    }
+
+    /// Set the login endpoint of the org, e.g. `https://test.salesforce.com`
+    pub fn login_endpoint(mut self, login_endpoint: &str) -> Self {
+        self.login_endpoint = Some(login_endpoint.to_string());
+        self
+    }
+
+    /// Set the API version used for the org, e.g. `v58.0`
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    pub(crate) fn login(&self) -> Result<Client, Error> {
+        let mut client = Client::new(Some(self.client_id.clone()), self.client_secret.clone());
+        if let Some(login_endpoint) = self.login_endpoint.as_ref() {
+            client.set_login_endpoint(login_endpoint);
+        }
+        if let Some(version) = self.version.as_ref() {
+            client.set_version(version);
+        }
+        match &self.credentials {
+            OrgCredentials::Password { username, password } => {
+                client.login_with_credential(username.clone(), password.clone())?;
+            }
+            OrgCredentials::RefreshToken(refresh_token) => {
+                client.refresh(refresh_token)?;
+            }
+            #[cfg(feature = "jwt")]
+            OrgCredentials::Jwt {
+                username,
+                private_key_file,
+            } => {
+                let credentials = JwtCredentials::from_pem_file(username, private_key_file)?;
+                client.login_with_jwt_credentials(&credentials)?;
+            }
+        };
+        Ok(client)
+    }
+}
+
+struct PoolEntry {
+    config: OrgConfig,
+    client: Option<Arc<Client>>,
+}
+
+/// Holds the clients of several orgs by alias. Each org is logged in lazily
+/// on first use, and again once its session expired
+#[derive(Default)]
+pub struct ClientPool {
+    orgs: RwLock<HashMap<String, Arc<Mutex<PoolEntry>>>>,
+}
+
+impl ClientPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an org to the pool, replacing any org with the same alias
+    pub fn add(&self, alias: &str, config: OrgConfig) {
+        let entry = PoolEntry {
+            config,
+            client: None,
+        };
+        self.orgs
+            .write()
+            .unwrap()
+            .insert(alias.to_string(), Arc::new(Mutex::new(entry)));
+    }
+
+    /// Removes an org from the pool. Returns false if the alias is unknown
+    pub fn remove(&self, alias: &str) -> bool {
+        self.orgs.write().unwrap().remove(alias).is_some()
+    }
+
+    /// Returns the aliases of the orgs of the pool
+    pub fn aliases(&self) -> Vec<String> {
+        self.orgs.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Returns the logged in client of an org. The org is logged in when it
+    /// is used for the first time or when its session expired, see
+    /// [is_session_valid](Client::is_session_valid)
+    pub fn get(&self, alias: &str) -> Result<Arc<Client>, Error> {
+        let entry = self
+            .orgs
+            .read()
+            .unwrap()
+            .get(alias)
+            .cloned()
+            .ok_or_else(|| Error::GenericError(format!("Unknown org alias: {}", alias)))?;
+
+        let mut entry = entry.lock().unwrap();
+        if let Some(client) = entry.client.as_ref() {
+            if client.is_session_valid().unwrap_or(false) {
+                return Ok(client.clone());
+            }
+        }
+        let client = Arc::new(entry.config.login()?);
+        entry.client = Some(client.clone());
+        Ok(client)
+    }
+
+    /// Drops the client of an org, so the next [get](ClientPool::get) logs in
+    /// again. Useful when a call failed because the session was revoked
+    pub fn invalidate(&self, alias: &str) {
+        if let Some(entry) = self.orgs.read().unwrap().get(alias) {
+            entry.lock().unwrap().client = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientPool, OrgConfig, OrgCredentials};
+    use crate::errors::Error;
+    use mockito::{Mock, Server as MockServer};
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn mock_login(server: &mut MockServer, hits: usize) -> Mock {
+        let issued_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        server
+            .mock("POST", "/services/oauth2/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "access_token": "this_is_access_token",
+                    "issued_at": issued_at.to_string(),
+                    "id": "https://login.salesforce.com/id/00Dxx/005xx",
+                    "instance_url": server.url(),
+                    "signature": "abcde",
+                    "token_type": "Bearer",
+                })
+                .to_string(),
+            )
+            .expect(hits)
+            .create()
+    }
+
+    fn org_config(server: &MockServer) -> OrgConfig {
+        OrgConfig::new(
+            "aaa",
+            Some("bbb"),
+            OrgCredentials::Password {
+                username: "u".to_string(),
+                password: "p".to_string(),
+            },
+        )
+        .login_endpoint(&server.url())
+    }
+
+    #[test]
+    fn logs_in_each_org_lazily() -> Result<(), Error> {
+        let mut server1 = MockServer::new_with_port(0);
+        let mut server2 = MockServer::new_with_port(0);
+        let login1 = mock_login(&mut server1, 1);
+        let login2 = mock_login(&mut server2, 0);
+
+        let pool = ClientPool::new();
+        pool.add("org1", org_config(&server1).version("v58.0"));
+        pool.add("org2", org_config(&server2));
+
+        let client = pool.get("org1")?;
+        assert_eq!("v58.0", client.version);
+        pool.get("org1")?;
+
+        login1.assert();
+        login2.assert();
+        assert!(pool.get("org3").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn logs_in_once_across_threads() -> Result<(), Error> {
+        let mut server = MockServer::new_with_port(0);
+        let login = mock_login(&mut server, 1);
+
+        let pool = Arc::new(ClientPool::new());
+        pool.add("org", org_config(&server));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                thread::spawn(move || pool.get("org").map(|_| ()))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap()?;
+        }
+        login.assert();
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalidate_logs_in_again() -> Result<(), Error> {
+        let mut server = MockServer::new_with_port(0);
+        let login = mock_login(&mut server, 2);
+
+        let pool = ClientPool::new();
+        pool.add("org", org_config(&server));
+        pool.get("org")?;
+        pool.invalidate("org");
+        pool.get("org")?;
+        login.assert();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::errors::Error;
+
+/// A token bucket shared by the threads using a client, see
+/// [set_rate_limit](crate::client::Client::set_rate_limit). The bucket holds
+/// up to a minute of requests and is refilled continuously
+pub(crate) struct RateLimiter {
+    max_requests_per_minute: u32,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(max_requests_per_minute: u32) -> Self {
+        RateLimiter {
+            max_requests_per_minute,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(max_requests_per_minute),
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token, waiting for one when the bucket is empty, or failing
+    /// with [Throttled](Error::Throttled) when `fail_fast` is set
+    pub(crate) fn acquire(&self, fail_fast: bool) -> Result<(), Error> {
+        loop {
+            let wait = match self.try_acquire() {
+                None => return Ok(()),
+                Some(wait) => wait,
+            };
+            if fail_fast {
+                return Err(Error::Throttled { retry_after: wait });
+            }
+            thread::sleep(wait);
+        }
+    }
+
+    /// Takes a token, or returns how long it takes for one to be available
+    fn try_acquire(&self) -> Option<Duration> {
+        let capacity = f64::from(self.max_requests_per_minute);
+        let per_second = capacity / 60.0;
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.refilled_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return None;
+        }
+        if per_second <= 0.0 {
+            return Some(Duration::from_secs(60));
+        }
+        Some(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use crate::errors::Error;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn fails_fast_when_exhausted() {
+        let limiter = RateLimiter::new(2);
+        assert!(limiter.acquire(true).is_ok());
+        assert!(limiter.acquire(true).is_ok());
+        match limiter.acquire(true) {
+            Err(Error::Throttled { retry_after }) => {
+                assert!(retry_after > Duration::from_secs(25));
+                assert!(retry_after <= Duration::from_secs(30));
+            }
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn blocks_across_threads() {
+        // 20 requests per second, after the first minute worth of requests
+        let limiter = Arc::new(RateLimiter::new(1200));
+        let start = Instant::now();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = limiter.clone();
+                thread::spawn(move || {
+                    for _ in 0..301 {
+                        limiter.acquire(false).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+}
@@ -1,14 +1,30 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// A page of records, or all of them when the pages were fetched by the
+/// client
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryResponse<T> {
+    /// The number of records matching the query as reported by Salesforce,
+    /// whatever the number of records fetched so far
     pub total_size: i32,
     pub done: bool,
     pub next_records_url: Option<String>,
     pub records: Vec<T>,
+    /// The number of records fetched, i.e. the length of `records`
+    #[serde(skip)]
+    pub collected: usize,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecordTypeResponse {
+    pub id: String,
+    pub developer_name: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -17,6 +33,135 @@ pub struct UpsertResponse {
     pub success: bool,
 }
 
+/// The status and headers of a response, e.g. to read the `Location` of a
+/// created record. Header names are lowercase
+#[derive(Clone, Debug)]
+pub struct ResponseMeta {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+}
+
+impl ResponseMeta {
+    pub(crate) fn from_response(res: &ureq::Response) -> Self {
+        let headers = res
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = res.header(&name)?.to_string();
+                Some((name.to_lowercase(), value))
+            })
+            .collect();
+        ResponseMeta {
+            status: res.status(),
+            headers,
+        }
+    }
+
+    /// Returns the value of a header, whatever the case of its name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(String::as_str)
+    }
+}
+
+/// The API calls made in the org in the last 24 hours, and how many are
+/// allowed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ApiUsage {
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl ApiUsage {
+    /// Parses the `Sforce-Limit-Info` header, e.g.
+    /// `api-usage=18/5000,per-app-api-usage=17/250(appName=sample-app)`
+    pub(crate) fn from_header(header: &str) -> Option<Self> {
+        let usage = header
+            .split(',')
+            .find_map(|part| part.trim().strip_prefix("api-usage="))?;
+        let (used, limit) = usage.split_once('/')?;
+        Some(ApiUsage {
+            used: used.trim().parse().ok()?,
+            limit: limit.trim().parse().ok()?,
+        })
+    }
+
+    /// The ratio of the limit which is used, from `0.0` to `1.0` or more
+    pub fn ratio(&self) -> f64 {
+        if self.limit == 0 {
+            return 1.0;
+        }
+        self.used as f64 / self.limit as f64
+    }
+}
+
+/// The maximum and remaining value of a limit of the org
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LimitEntry {
+    pub max: i64,
+    pub remaining: i64,
+}
+
+/// The limits of the org. Limits without a field of their own, e.g. the ones
+/// added by a newer release, are kept in `other`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct LimitsResponse {
+    pub daily_api_requests: Option<LimitEntry>,
+    pub daily_async_apex_executions: Option<LimitEntry>,
+    pub daily_bulk_api_batches: Option<LimitEntry>,
+    #[serde(rename = "DailyBulkV2QueryJobs")]
+    pub daily_bulk_v2_query_jobs: Option<LimitEntry>,
+    #[serde(rename = "DailyBulkV2QueryFileStorageMB")]
+    pub daily_bulk_v2_query_file_storage_mb: Option<LimitEntry>,
+    pub daily_streaming_api_events: Option<LimitEntry>,
+    #[serde(rename = "DataStorageMB")]
+    pub data_storage_mb: Option<LimitEntry>,
+    #[serde(rename = "FileStorageMB")]
+    pub file_storage_mb: Option<LimitEntry>,
+    pub hourly_published_platform_events: Option<LimitEntry>,
+    pub single_email: Option<LimitEntry>,
+    #[serde(flatten)]
+    pub other: HashMap<String, LimitEntry>,
+}
+
+impl LimitsResponse {
+    /// Returns the API requests remaining for the current 24 hours
+    pub fn remaining_api_requests(&self) -> Option<i64> {
+        self.daily_api_requests.map(|limit| limit.remaining)
+    }
+
+    /// Returns a limit by its name, e.g. `DailyApiRequests`, whether it has a
+    /// field of its own or not
+    pub fn get(&self, name: &str) -> Option<LimitEntry> {
+        let typed = match name {
+            "DailyApiRequests" => self.daily_api_requests,
+            "DailyAsyncApexExecutions" => self.daily_async_apex_executions,
+            "DailyBulkApiBatches" => self.daily_bulk_api_batches,
+            "DailyBulkV2QueryJobs" => self.daily_bulk_v2_query_jobs,
+            "DailyBulkV2QueryFileStorageMB" => self.daily_bulk_v2_query_file_storage_mb,
+            "DailyStreamingApiEvents" => self.daily_streaming_api_events,
+            "DataStorageMB" => self.data_storage_mb,
+            "FileStorageMB" => self.file_storage_mb,
+            "HourlyPublishedPlatformEvents" => self.hourly_published_platform_events,
+            "SingleEmail" => self.single_email,
+            _ => None,
+        };
+        typed.or_else(|| self.other.get(name).copied())
+    }
+}
+
+/// The result of an upsert. `created` tells whether the record was inserted
+/// rather than updated
+#[derive(Deserialize, Debug)]
+pub struct UpsertResult {
+    /// The id of the record, `None` when Salesforce answers an update with
+    /// `204 No Content`, as it does before API version 46.0
+    pub id: Option<String>,
+    #[serde(default)]
+    pub created: bool,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct CompositeResponse {
     pub id: Option<String>,
@@ -43,6 +188,194 @@ pub struct CompositeBodyRequest<T> {
     pub records: Vec<T>,
 }
 
+/// A subrequest of the `/composite` endpoint. The `url` may be relative to the
+/// versioned data path (e.g. `/sobjects/Account`) and can reference the result
+/// of a previous subrequest with `@{referenceId.field}`
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubRequest {
+    pub method: String,
+    pub url: String,
+    pub reference_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// Builds the subrequests of a `/composite` request
+#[derive(Default, Debug)]
+pub struct CompositeRequest {
+    requests: Vec<SubRequest>,
+}
+
+impl CompositeRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a subrequest
+    pub fn add(mut self, method: &str, url: &str, reference_id: &str, body: Option<Value>) -> Self {
+        self.requests.push(SubRequest {
+            method: method.to_string(),
+            url: url.to_string(),
+            reference_id: reference_id.to_string(),
+            body,
+        });
+        self
+    }
+
+    pub fn get(self, url: &str, reference_id: &str) -> Self {
+        self.add("GET", url, reference_id, None)
+    }
+
+    pub fn post(self, url: &str, reference_id: &str, body: Value) -> Self {
+        self.add("POST", url, reference_id, Some(body))
+    }
+
+    pub fn patch(self, url: &str, reference_id: &str, body: Value) -> Self {
+        self.add("PATCH", url, reference_id, Some(body))
+    }
+
+    pub fn delete(self, url: &str, reference_id: &str) -> Self {
+        self.add("DELETE", url, reference_id, None)
+    }
+
+    pub fn build(self) -> Vec<SubRequest> {
+        self.requests
+    }
+}
+
+/// A subrequest of the `/composite/batch` endpoint. The `url` may be relative
+/// to the versioned data path (e.g. `/sobjects/Account`). `rich_input` is the
+/// body of the subrequest
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSubRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rich_input: Option<Value>,
+}
+
+impl BatchSubRequest {
+    pub fn new(method: &str, url: &str, rich_input: Option<Value>) -> Self {
+        BatchSubRequest {
+            method: method.to_string(),
+            url: url.to_string(),
+            rich_input,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeBatchRequestBody {
+    pub halt_on_error: bool,
+    pub batch_requests: Vec<BatchSubRequest>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeBatchResponse {
+    pub has_errors: bool,
+    pub results: Vec<BatchResult>,
+}
+
+/// The result of a subrequest of the `/composite/batch` endpoint, in the
+/// order of the subrequests
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    pub status_code: u16,
+    pub result: Option<Value>,
+}
+
+impl BatchResult {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status_code)
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeRequestBody {
+    pub all_or_none: bool,
+    pub composite_request: Vec<SubRequest>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeResult {
+    pub composite_response: Vec<SubResponse>,
+}
+
+impl CompositeResult {
+    /// Returns the response of the subrequest with the given reference id
+    pub fn get(&self, reference_id: &str) -> Option<&SubResponse> {
+        self.composite_response
+            .iter()
+            .find(|response| response.reference_id == reference_id)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubResponse {
+    pub body: Value,
+    #[serde(default)]
+    pub http_headers: HashMap<String, String>,
+    pub http_status_code: u16,
+    pub reference_id: String,
+}
+
+impl SubResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.http_status_code)
+    }
+}
+
+/// A graph of the `/composite/graph` endpoint. Each graph is processed in its
+/// own transaction
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Graph {
+    pub graph_id: String,
+    pub composite_request: Vec<SubRequest>,
+}
+
+impl Graph {
+    pub fn new(graph_id: &str, requests: Vec<SubRequest>) -> Self {
+        Graph {
+            graph_id: graph_id.to_string(),
+            composite_request: requests,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompositeGraphRequestBody {
+    pub graphs: Vec<Graph>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CompositeGraphResult {
+    pub graphs: Vec<GraphResult>,
+}
+
+impl CompositeGraphResult {
+    /// Returns the result of the graph with the given id
+    pub fn get(&self, graph_id: &str) -> Option<&GraphResult> {
+        self.graphs.iter().find(|graph| graph.graph_id == graph_id)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphResult {
+    pub graph_id: String,
+    pub graph_response: CompositeResult,
+    pub is_successful: bool,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct RecordsResponse {
     pub id: String,
@@ -71,6 +404,48 @@ pub struct ErrorResponse {
     pub message: Value,
     pub error_code: String,
     pub fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub duplicate_result: Option<DuplicateResult>,
+}
+
+/// The duplicate rule which rejected a record, and the records it matched
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateResult {
+    pub allow_save: bool,
+    pub duplicate_rule: String,
+    pub duplicate_rule_entity_type: String,
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub match_results: Vec<DuplicateMatchResult>,
+}
+
+impl DuplicateResult {
+    /// Returns the ids of all the matched records
+    pub fn matched_ids(&self) -> Vec<&str> {
+        self.match_results
+            .iter()
+            .flat_map(|match_result| &match_result.match_records)
+            .filter_map(|match_record| match_record.record.get("Id")?.as_str())
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMatchResult {
+    pub entity_type: String,
+    pub match_engine: String,
+    pub rule: String,
+    #[serde(default)]
+    pub match_records: Vec<DuplicateMatchRecord>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMatchRecord {
+    pub match_confidence: f64,
+    pub record: Value,
 }
 
 #[derive(Deserialize, Debug)]
@@ -81,15 +456,93 @@ pub struct TokenResponse {
     pub instance_url: String,
     pub signature: String,
     pub token_type: Option<String>,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
 }
 
-#[derive(Debug)]
+/// The org and user a client is logged in as, parsed from the identity URL
+/// (`https://login.salesforce.com/id/{orgId}/{userId}`) of the token response
+#[derive(Clone, PartialEq, Debug)]
+pub struct LoginIdentity {
+    pub org_id: String,
+    pub user_id: String,
+    pub identity_url: String,
+}
+
+impl LoginIdentity {
+    /// Parses an identity URL. Any host is accepted (login, test or My
+    /// Domain), `None` is returned when the path is not `/id/{orgId}/{userId}`
+    pub fn from_identity_url(identity_url: &str) -> Option<Self> {
+        let path = identity_url.split(['?', '#']).next()?;
+        let (_, ids) = path.rsplit_once("/id/")?;
+        let mut ids = ids.trim_end_matches('/').split('/');
+        let org_id = ids.next().filter(|id| !id.is_empty())?;
+        let user_id = ids.next().filter(|id| !id.is_empty())?;
+        if ids.next().is_some() {
+            return None;
+        }
+        Some(LoginIdentity {
+            org_id: org_id.to_string(),
+            user_id: user_id.to_string(),
+            identity_url: identity_url.to_string(),
+        })
+    }
+}
+
+/// The OpenID Connect discovery document (`/.well-known/openid-configuration`)
+#[derive(Deserialize, Debug)]
+pub struct OidcConfig {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: Option<String>,
+    pub revocation_endpoint: Option<String>,
+    pub introspection_endpoint: Option<String>,
+    pub jwks_uri: Option<String>,
+    #[serde(default)]
+    pub scopes_supported: Vec<String>,
+    #[serde(default)]
+    pub response_types_supported: Vec<String>,
+}
+
+/// The authenticated state of a client, which can be persisted and restored
+/// with `Client::session` and `Client::restore_session`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Session {
+    pub access_token: String,
+    pub instance_url: String,
+    pub issued_at: String,
+    pub version: String,
+}
+
+#[derive(Clone, Debug)]
 pub struct AccessToken {
     pub token_type: String,
     pub value: String,
     pub issued_at: String,
 }
 
+impl AccessToken {
+    /// Returns when the token was issued. `issued_at` is expressed in
+    /// milliseconds since the epoch by Salesforce, `None` is returned when it
+    /// is unknown, e.g. for tokens set with `Client::set_access_token`
+    pub fn issued_at_time(&self) -> Option<SystemTime> {
+        let millis = self.issued_at.parse::<u64>().ok()?;
+        UNIX_EPOCH.checked_add(Duration::from_millis(millis))
+    }
+}
+
+/// The result of a successful SOAP login
+#[derive(Debug, Clone)]
+pub struct SoapLoginResult {
+    pub session_id: String,
+    pub server_url: String,
+    pub user_id: String,
+    pub organization_id: String,
+    pub password_expired: bool,
+    pub sandbox: bool,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DescribeResponse {
@@ -140,6 +593,9 @@ pub struct Field {
     pub controller_name: Option<String>,
     pub createable: bool,
     pub custom: bool,
+    /// The default value of the field, e.g. a boolean for a checkbox or the
+    /// value of the default picklist entry
+    pub default_value: Option<Value>,
     pub default_value_formula: Option<String>,
     pub defaulted_on_create: bool,
     pub dependent_picklist: bool,
@@ -150,7 +606,7 @@ pub struct Field {
     pub external_id: bool,
     pub extra_type_info: Option<String>,
     pub filterable: bool,
-    pub filtered_lookup_info: Option<String>,
+    pub filtered_lookup_info: Option<Value>,
     pub formula_treat_null_number_as_zero: bool,
     pub groupable: bool,
     pub high_scale_number: bool,
@@ -166,6 +622,8 @@ pub struct Field {
     pub name_pointing: bool,
     pub nillable: bool,
     pub permissionable: bool,
+    #[serde(default)]
+    pub picklist_values: Vec<PicklistEntry>,
     pub polymorphic_foreign_key: bool,
     pub precision: u8,
     pub query_by_distance: bool,
@@ -185,6 +643,25 @@ pub struct Field {
     pub write_requires_master_read: bool,
 }
 
+impl Field {
+    /// Returns the active entries of a picklist field
+    pub fn active_picklist_values(&self) -> impl Iterator<Item = &PicklistEntry> {
+        self.picklist_values.iter().filter(|entry| entry.active)
+    }
+}
+
+/// An entry of a picklist field. `valid_for` is the base64 bitmap of the
+/// controlling values of a dependent picklist
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PicklistEntry {
+    pub active: bool,
+    pub default_value: bool,
+    pub label: Option<String>,
+    pub valid_for: Option<String>,
+    pub value: String,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ChildRelationship {
@@ -214,6 +691,85 @@ pub struct Urls {
     pub sobject: String,
 }
 
+/// The compact layouts of an SObject, see
+/// [compact_layouts](crate::client::Client::compact_layouts)
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactLayoutsResponse {
+    pub compact_layouts: Vec<CompactLayout>,
+    pub default_compact_layout_id: Option<String>,
+    #[serde(default)]
+    pub record_type_compact_layout_mappings: Vec<RecordTypeCompactLayoutMapping>,
+}
+
+/// A compact layout. The items are kept as returned by Salesforce
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactLayout {
+    pub id: Option<String>,
+    pub label: String,
+    pub name: String,
+    pub object_type: Option<String>,
+    #[serde(default)]
+    pub field_items: Vec<Value>,
+    #[serde(default)]
+    pub actions: Vec<Value>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordTypeCompactLayoutMapping {
+    pub available: bool,
+    pub compact_layout_id: Option<String>,
+    pub compact_layout_name: String,
+    pub record_type_id: String,
+    pub record_type_name: String,
+}
+
+/// The default values of a new record, see
+/// [record_defaults](crate::client::Client::record_defaults). The layout and
+/// the object infos are kept as returned by Salesforce
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordDefaultsResponse {
+    pub layout: Option<Value>,
+    #[serde(default)]
+    pub object_infos: HashMap<String, Value>,
+    pub record: RecordDefaults,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordDefaults {
+    pub api_name: String,
+    pub record_type_id: Option<String>,
+    pub fields: HashMap<String, FieldValue>,
+}
+
+/// The value of a field in the UI API, with its display value, e.g. the
+/// label of a picklist value
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldValue {
+    pub display_value: Option<String>,
+    pub value: Value,
+}
+
+/// A field definition from the Tooling API, which exposes data governance
+/// attributes missing from the describe
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct FieldDefinition {
+    pub durable_id: String,
+    pub qualified_api_name: String,
+    pub label: String,
+    pub data_type: String,
+    pub description: Option<String>,
+    pub business_status: Option<String>,
+    pub compliance_group: Option<String>,
+    pub security_classification: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DescribeGlobalResponse {
@@ -222,6 +778,28 @@ pub struct DescribeGlobalResponse {
     pub sobjects: Vec<DescribeGlobalSObjectResponse>,
 }
 
+impl DescribeGlobalResponse {
+    /// Returns the objects which can be queried
+    pub fn queryable(&self) -> impl Iterator<Item = &DescribeGlobalSObjectResponse> {
+        self.sobjects.iter().filter(|sobject| sobject.queryable)
+    }
+
+    /// Returns the objects whose records can be created
+    pub fn createable(&self) -> impl Iterator<Item = &DescribeGlobalSObjectResponse> {
+        self.sobjects.iter().filter(|sobject| sobject.createable)
+    }
+
+    /// Returns the objects whose records can be updated
+    pub fn updateable(&self) -> impl Iterator<Item = &DescribeGlobalSObjectResponse> {
+        self.sobjects.iter().filter(|sobject| sobject.updateable)
+    }
+
+    /// Returns the objects whose records can be deleted
+    pub fn deletable(&self) -> impl Iterator<Item = &DescribeGlobalSObjectResponse> {
+        self.sobjects.iter().filter(|sobject| sobject.deletable)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DescribeGlobalSObjectResponse {
@@ -251,12 +829,89 @@ pub struct DescribeGlobalSObjectResponse {
     pub urls: HashMap<String, String>,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUpdatedResponse {
+    pub ids: Vec<String>,
+    pub latest_date_covered: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDeletedResponse {
+    pub deleted_records: Vec<DeletedRecord>,
+    pub earliest_date_available: Option<String>,
+    pub latest_date_covered: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedRecord {
+    pub id: String,
+    pub deleted_date: String,
+}
+
+/// An approval request waiting for a user, i.e. a `ProcessInstanceWorkitem`
+/// record. Its `id` is the one to approve or reject the request with
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApprovalWorkItem {
+    pub id: String,
+    pub process_instance_id: String,
+    pub process_instance: ApprovalProcessInstance,
+    pub actor_id: String,
+    pub created_date: String,
+}
+
+/// The approval process run for a record, see
+/// [ApprovalWorkItem](ApprovalWorkItem)
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApprovalProcessInstance {
+    /// The id of the record submitted for approval
+    pub target_object_id: String,
+    /// The record submitted for approval, with its `Name`
+    pub target_object: Option<Polymorphic>,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchResponse {
     pub search_records: Vec<SearchRecord>,
 }
 
+/// The response of the GraphQL API. Errors may be returned along with
+/// partial data
+#[derive(Deserialize, Debug)]
+pub struct GraphQlResponse<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<GraphQlError>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GraphQlError {
+    pub message: String,
+    #[serde(default)]
+    pub locations: Vec<GraphQlErrorLocation>,
+    pub path: Option<Vec<Value>>,
+    pub extensions: Option<Value>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GraphQlErrorLocation {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// The records found by a SOSL search, with the fields of its `RETURNING`
+/// clause
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedSearchResponse<T> {
+    pub search_records: Vec<T>,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchRecord {
@@ -273,6 +928,36 @@ pub struct SObjectAttribute {
     pub url: String,
 }
 
+/// A polymorphic relationship field (e.g. `What` or `Who` on Task), which can
+/// reference records of several object types. `fields` holds the queried
+/// fields, which default to a [Value](serde_json::Value) to be deserialized
+/// once the referenced type is known
+#[derive(Deserialize, Debug)]
+pub struct Polymorphic<T = Value> {
+    pub attributes: SObjectAttribute,
+    #[serde(flatten)]
+    pub fields: T,
+}
+
+impl<T> Polymorphic<T> {
+    /// Returns the type of the referenced record, e.g. `Account`
+    pub fn sobject_type(&self) -> &str {
+        &self.attributes.sobject_type
+    }
+
+    /// Returns true when the referenced record is of the given type
+    pub fn is(&self, sobject_type: &str) -> bool {
+        self.attributes.sobject_type == sobject_type
+    }
+}
+
+impl Polymorphic<Value> {
+    /// Deserializes the fields of the referenced record into `U`
+    pub fn fields_as<U: DeserializeOwned>(&self) -> Result<U, serde_json::Error> {
+        U::deserialize(&self.fields)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct VersionResponse {
@@ -280,3 +965,12 @@ pub struct VersionResponse {
     pub url: String,
     pub version: String,
 }
+
+impl VersionResponse {
+    /// Returns the major and minor numbers of the version, e.g. `(58, 0)`
+    /// for `58.0`, to compare versions numerically
+    pub fn number(&self) -> Option<(u32, u32)> {
+        let (major, minor) = self.version.trim_start_matches('v').split_once('.')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+}
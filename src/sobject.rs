@@ -0,0 +1,157 @@
+use serde::Serialize;
+
+use crate::client::Client;
+use crate::errors::Error;
+use crate::response::{CompositeResponse, RecordRequest, RecordRequestAttribute, UpsertResponse};
+
+/// A struct mapped to an SObject type, so that it can be saved without
+/// passing the type or embedding the `attributes` of the record
+///
+/// ```rust
+/// use rust_sync_force::sobject::SObject;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct Account {
+///     name: String,
+/// }
+///
+/// impl SObject for Account {
+///     const API_NAME: &'static str = "Account";
+/// }
+/// ```
+pub trait SObject: Serialize {
+    /// The API name of the SObject type, e.g. `Account` or `Invoice__c`
+    const API_NAME: &'static str;
+}
+
+impl Client {
+    /// Inserts an SObject of the type of the record
+    pub fn insert_obj<T: SObject>(&self, record: &T) -> Result<UpsertResponse, Error> {
+        self.insert(T::API_NAME, record)
+    }
+
+    /// Inserts multiple SObjects, with the `attributes` set from the type of
+    /// the records
+    pub fn inserts_obj<T: SObject>(
+        &self,
+        all_or_none: bool,
+        records: &[T],
+    ) -> Result<Vec<Result<CompositeResponse, Error>>, Error> {
+        self.inserts(all_or_none, with_attributes(records))
+    }
+
+    /// Updates an SObject of the type of the record
+    pub fn update_obj<T: SObject>(&self, id: &str, record: &T) -> Result<(), Error> {
+        self.update(T::API_NAME, id, record)
+    }
+
+    /// Updates multiple SObjects, with the `attributes` set from the type of
+    /// the records. The records must have their `Id`
+    pub fn updates_obj<T: SObject>(
+        &self,
+        all_or_none: bool,
+        records: &[T],
+    ) -> Result<Vec<Result<CompositeResponse, Error>>, Error> {
+        self.updates(all_or_none, with_attributes(records))
+    }
+}
+
+fn with_attributes<T: SObject>(records: &[T]) -> Vec<RecordRequest<&T>> {
+    records
+        .iter()
+        .map(|record| RecordRequest {
+            attributes: RecordRequestAttribute {
+                sobject_type: T::API_NAME.to_string(),
+            },
+            record,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SObject;
+    use crate::client::Client;
+    use crate::errors::Error;
+    use mockito::Server as MockServer;
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Account {
+        name: String,
+    }
+
+    impl SObject for Account {
+        const API_NAME: &'static str = "Account";
+    }
+
+    fn create_test_client(server: &MockServer) -> Client {
+        let mut client = Client::new(None, None);
+        client.set_instance_url(&server.url());
+        client.set_access_token("this_is_access_token");
+        client
+    }
+
+    #[test]
+    fn insert_obj() -> Result<(), Error> {
+        let mut server = MockServer::new_with_port(0);
+        let _m = server
+            .mock("POST", "/services/data/v56.0/sobjects/Account")
+            .match_body(mockito::Matcher::Json(json!({ "Name": "foo" })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "id": "12345", "success": true }).to_string())
+            .create();
+
+        let client = create_test_client(&server);
+        let r = client.insert_obj(&Account {
+            name: "foo".to_string(),
+        })?;
+        assert_eq!("12345", r.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn inserts_obj() -> Result<(), Error> {
+        let mut server = MockServer::new_with_port(0);
+        let _m = server
+            .mock("POST", "/services/data/v56.0/composite/sobjects")
+            .match_body(mockito::Matcher::Json(json!({
+                "allOrNone": true,
+                "records": [
+                    { "attributes": { "type": "Account" }, "Name": "foo" },
+                    { "attributes": { "type": "Account" }, "Name": "bar" },
+                ]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([
+                    { "id": "001x1", "success": true, "errors": [] },
+                    { "id": "001x2", "success": true, "errors": [] },
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let client = create_test_client(&server);
+        let records = vec![
+            Account {
+                name: "foo".to_string(),
+            },
+            Account {
+                name: "bar".to_string(),
+            },
+        ];
+        let r = client.inserts_obj(true, &records)?;
+        assert_eq!(2, r.len());
+        assert!(r.iter().all(Result::is_ok));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+//! Counters of the requests sent by a [Client](crate::client::Client), kept
+//! without any setup, see [stats](crate::client::Client::stats)
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// The upper bounds of the latency buckets. The last bucket of
+/// [latency_buckets](EndpointStats::latency_buckets) holds the requests
+/// slower than all of them
+pub const LATENCY_BOUNDS: [Duration; 8] = [
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+const BUCKETS: usize = LATENCY_BOUNDS.len() + 1;
+
+/// The kind of API a request is sent to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `query` and `queryAll`, including the next pages and Tooling queries
+    Query,
+    /// `composite`, along with SObject Collections, batches and graphs
+    Composite,
+    /// `sobjects`, i.e. the CRUD of records and the describes
+    SObject,
+    /// The CometD requests of a [CometdClient](crate::stream::CometdClient)
+    Streaming,
+    Other,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 5] = [
+        Endpoint::Query,
+        Endpoint::Composite,
+        Endpoint::SObject,
+        Endpoint::Streaming,
+        Endpoint::Other,
+    ];
+
+    pub(crate) fn from_url(url: &str) -> Self {
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let has_segment = |names: &[&str]| path.split('/').any(|segment| names.contains(&segment));
+        if has_segment(&["cometd"]) {
+            Endpoint::Streaming
+        } else if has_segment(&["composite"]) {
+            Endpoint::Composite
+        } else if has_segment(&["query", "queryAll"]) {
+            Endpoint::Query
+        } else if has_segment(&["sobjects"]) {
+            Endpoint::SObject
+        } else {
+            Endpoint::Other
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The counters of an [Endpoint](Endpoint) at a point in time
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EndpointStats {
+    /// The calls made, e.g. one per page of a query
+    pub operations: u64,
+    /// The requests sent, i.e. the operations along with the requests sent
+    /// again after refreshing the session or by the
+    /// [retry policy](crate::client::Client::set_retry_policy)
+    pub attempts: u64,
+    /// The operations which failed once retried
+    pub errors: u64,
+    /// The number of attempts per latency, see [LATENCY_BOUNDS](LATENCY_BOUNDS)
+    pub latency_buckets: [u64; BUCKETS],
+    /// The sum of the latencies of the attempts
+    pub total_latency: Duration,
+}
+
+impl EndpointStats {
+    /// The requests sent again, after a failure
+    pub fn retries(&self) -> u64 {
+        self.attempts.saturating_sub(self.operations)
+    }
+
+    /// The average latency of the attempts, `None` before the first one
+    pub fn mean_latency(&self) -> Option<Duration> {
+        let attempts = u32::try_from(self.attempts).ok().filter(|n| *n > 0)?;
+        Some(self.total_latency / attempts)
+    }
+
+    fn add(&mut self, other: &EndpointStats) {
+        self.operations += other.operations;
+        self.attempts += other.attempts;
+        self.errors += other.errors;
+        for (bucket, count) in self.latency_buckets.iter_mut().zip(other.latency_buckets) {
+            *bucket += count;
+        }
+        self.total_latency += other.total_latency;
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    operations: AtomicU64,
+    attempts: AtomicU64,
+    errors: AtomicU64,
+    latency_buckets: [AtomicU64; BUCKETS],
+    total_latency_micros: AtomicU64,
+}
+
+/// The counters of the requests of a client per [Endpoint](Endpoint),
+/// updated atomically so that the client can be shared between threads
+#[derive(Default)]
+pub struct ClientStats {
+    endpoints: [Counters; Endpoint::ALL.len()],
+}
+
+impl ClientStats {
+    /// Returns the counters of the requests sent to `endpoint`
+    pub fn endpoint(&self, endpoint: Endpoint) -> EndpointStats {
+        let counters = &self.endpoints[endpoint.index()];
+        EndpointStats {
+            operations: counters.operations.load(Ordering::Relaxed),
+            attempts: counters.attempts.load(Ordering::Relaxed),
+            errors: counters.errors.load(Ordering::Relaxed),
+            latency_buckets: std::array::from_fn(|i| {
+                counters.latency_buckets[i].load(Ordering::Relaxed)
+            }),
+            total_latency: Duration::from_micros(
+                counters.total_latency_micros.load(Ordering::Relaxed),
+            ),
+        }
+    }
+
+    /// Returns the counters of all the requests
+    pub fn total(&self) -> EndpointStats {
+        let mut total = EndpointStats::default();
+        for endpoint in Endpoint::ALL {
+            total.add(&self.endpoint(endpoint));
+        }
+        total
+    }
+
+    /// Sets all the counters back to zero. Requests in flight while resetting
+    /// may be counted partly
+    pub fn reset(&self) {
+        for counters in &self.endpoints {
+            counters.operations.store(0, Ordering::Relaxed);
+            counters.attempts.store(0, Ordering::Relaxed);
+            counters.errors.store(0, Ordering::Relaxed);
+            for bucket in &counters.latency_buckets {
+                bucket.store(0, Ordering::Relaxed);
+            }
+            counters.total_latency_micros.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn record_operation(&self, endpoint: Endpoint) {
+        self.endpoints[endpoint.index()]
+            .operations
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_attempt(&self, endpoint: Endpoint, elapsed: Duration) {
+        let counters = &self.endpoints[endpoint.index()];
+        counters.attempts.fetch_add(1, Ordering::Relaxed);
+        let bucket = LATENCY_BOUNDS
+            .iter()
+            .position(|bound| elapsed <= *bound)
+            .unwrap_or(LATENCY_BOUNDS.len());
+        counters.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        counters.total_latency_micros.fetch_add(
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    pub(crate) fn record_error(&self, endpoint: Endpoint) {
+        self.endpoints[endpoint.index()]
+            .errors
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientStats, Endpoint};
+    use std::time::Duration;
+
+    #[test]
+    fn endpoint_from_url() {
+        let cases = [
+            (
+                "https://ap.salesforce.com/services/data/v56.0/query/?q=SELECT",
+                Endpoint::Query,
+            ),
+            ("/services/data/v56.0/query/01gxx-2000", Endpoint::Query),
+            ("/services/data/v56.0/tooling/query/", Endpoint::Query),
+            ("/services/data/v56.0/queryAll/", Endpoint::Query),
+            (
+                "/services/data/v56.0/composite/sobjects",
+                Endpoint::Composite,
+            ),
+            (
+                "/services/data/v56.0/sobjects/Account/001",
+                Endpoint::SObject,
+            ),
+            (
+                "/services/data/v56.0/sobjects/Query__c/001",
+                Endpoint::SObject,
+            ),
+            ("/cometd/56.0", Endpoint::Streaming),
+            ("/services/data/v56.0/limits/", Endpoint::Other),
+        ];
+        for (url, endpoint) in cases {
+            assert_eq!(endpoint, Endpoint::from_url(url), "{}", url);
+        }
+    }
+
+    #[test]
+    fn records_and_resets() {
+        let stats = ClientStats::default();
+        stats.record_operation(Endpoint::Query);
+        stats.record_attempt(Endpoint::Query, Duration::from_millis(20));
+        stats.record_attempt(Endpoint::Query, Duration::from_millis(300));
+        stats.record_attempt(Endpoint::Query, Duration::from_secs(60));
+        stats.record_error(Endpoint::Query);
+        stats.record_operation(Endpoint::SObject);
+        stats.record_attempt(Endpoint::SObject, Duration::from_millis(40));
+
+        let query = stats.endpoint(Endpoint::Query);
+        assert_eq!(1, query.operations);
+        assert_eq!(3, query.attempts);
+        assert_eq!(2, query.retries());
+        assert_eq!(1, query.errors);
+        assert_eq!([1, 0, 0, 1, 0, 0, 0, 0, 1], query.latency_buckets);
+        assert_eq!(
+            Some(Duration::from_nanos(20_106_666_666)),
+            query.mean_latency()
+        );
+
+        let total = stats.total();
+        assert_eq!(2, total.operations);
+        assert_eq!(4, total.attempts);
+        assert_eq!([2, 0, 0, 1, 0, 0, 0, 0, 1], total.latency_buckets);
+
+        stats.reset();
+        assert_eq!(0, stats.total().attempts);
+        assert_eq!(None, stats.endpoint(Endpoint::Query).mean_latency());
+    }
+}
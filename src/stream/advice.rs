@@ -17,9 +17,11 @@ pub enum Reconnect {
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct Advice {
     pub reconnect: Reconnect,
+    /// How long, in milliseconds, the server holds a `connect` request.
     pub timeout: Option<u32>,
+    /// How long, in milliseconds, the client should wait before reconnecting.
     pub interval: Option<u32>,
-    #[serde(rename = "kebab-case")]
+    #[serde(rename = "multiple-clients")]
     pub multiple_clients: Option<bool>,
     pub hosts: Option<Vec<String>>,
 }
@@ -1,5 +1,8 @@
+use log::{debug, warn};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
 use ureq::Response;
 
 use crate::client::Client;
@@ -7,9 +10,17 @@ use crate::errors::Error;
 use crate::stream::advice::{Advice, Reconnect};
 use crate::stream::config::{COMETD_SUPPORTED_TYPES, COMETD_VERSION};
 use crate::stream::StreamResponse;
+use crate::utils::parse_json;
 
 use super::response::ErroredResponse;
 
+/// The default timeout of `/meta/connect` requests. Salesforce holds them
+/// for up to 110 seconds
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The margin added to the advised timeout for the network round trip
+const NETWORK_DELAY: Duration = Duration::from_secs(10);
+
 /// The cometd client.
 pub struct CometdClient {
     client: Client,
@@ -17,6 +28,10 @@ pub struct CometdClient {
     max_retries: i8,
     actual_retries: i8,
     subscriptions: HashMap<String, i64>,
+    last_connect_at: Option<Instant>,
+    replay_fallback: bool,
+    timeout: Duration,
+    advised_timeout: Option<Duration>,
 }
 
 #[derive(Serialize, Debug)]
@@ -68,6 +83,8 @@ where
     pub channel: &'a str,
     pub client_id: &'a str,
     pub data: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<&'a str>,
 }
 
 impl CometdClient {
@@ -79,6 +96,10 @@ impl CometdClient {
             actual_retries: 0,
             max_retries: 3,
             subscriptions,
+            last_connect_at: None,
+            replay_fallback: false,
+            timeout: DEFAULT_CONNECT_TIMEOUT,
+            advised_timeout: None,
         }
     }
 
@@ -89,24 +110,77 @@ impl CometdClient {
         self
     }
 
+    /// Sets the timeout of the long-polling `/meta/connect` requests, which
+    /// overrides the timeout of the agent of the client. It is extended when
+    /// the server advises a longer one. Defaults to 120 seconds
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the timeout used for `/meta/connect` requests: the configured
+    /// one, or the advised one plus some network delay if longer
+    fn connect_timeout(&self) -> Duration {
+        self.advised_timeout
+            .map(|advised_timeout| advised_timeout + NETWORK_DELAY)
+            .map_or(self.timeout, |advised_timeout| {
+                advised_timeout.max(self.timeout)
+            })
+    }
+
+    /// When enabled, a subscription whose replay id is no longer retained by
+    /// the server (after 24 or 72 hours) falls back to `-1`, i.e. new events
+    /// only, instead of failing with [ReplayIdExpired](Error::ReplayIdExpired).
+    /// Events published in between are lost.
+    pub fn set_replay_fallback(mut self, replay_fallback: bool) -> Self {
+        self.replay_fallback = replay_fallback;
+        self
+    }
+
+    /// Returns the replay id of the last event received on each channel, or
+    /// the initial one if none was received yet. Persisting them and passing
+    /// them to [new](CometdClient::new) resumes the stream without losing
+    /// events, within the retention window of the server.
+    pub fn replay_ids(&self) -> HashMap<String, i64> {
+        self.subscriptions.clone()
+    }
+
+    /// Returns the client the stream was created with, e.g. to inspect its
+    /// session
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Returns when the last successful `connect` round trip happened, including
+    /// the empty `/meta/connect` acknowledgements the server sends as heartbeats.
+    /// This allows a watchdog to tell an idle stream from a stuck one.
+    pub fn last_connect_at(&self) -> Option<Instant> {
+        self.last_connect_at
+    }
+
+    fn cometd_path(&self) -> String {
+        format!("/cometd/{}", self.client.version.replace("v", ""))
+    }
+
     fn send_request(&self, body: &impl Serialize) -> Result<Response, Error> {
-        self.client.sfdc_post(
-            format!("/cometd/{}", self.client.version.replace("v", "")),
-            body,
-        )
+        self.client.sfdc_post(self.cometd_path(), body)
     }
 
     fn retry(&mut self) -> Result<Vec<StreamResponse>, Error> {
         self.actual_retries += 1;
-        println!("Attempt n°{}", self.actual_retries);
+        debug!("Sending /meta/connect, attempt {}", self.actual_retries);
 
         match &self.stream_client_id {
             Some(stream_client_id) => {
-                let response = self.send_request(&ConnectPayload {
-                    channel: "/meta/connect",
-                    client_id: &stream_client_id,
-                    connection_type: "long-polling",
-                })?;
+                let response = self.client.sfdc_post_with_timeout(
+                    self.cometd_path(),
+                    &ConnectPayload {
+                        channel: "/meta/connect",
+                        client_id: stream_client_id,
+                        connection_type: "long-polling",
+                    },
+                    self.connect_timeout(),
+                )?;
 
                 self.handle_response(response)
             }
@@ -118,7 +192,7 @@ impl CometdClient {
 
     fn retry_handshake(&mut self) -> Result<Vec<StreamResponse>, Error> {
         self.actual_retries += 1;
-        println!("Attempt n°{}", self.actual_retries);
+        debug!("Sending /meta/handshake, attempt {}", self.actual_retries);
 
         let response = self.send_request(&HandshakePayload {
             channel: "/meta/handshake",
@@ -134,10 +208,20 @@ impl CometdClient {
         advice: &Advice,
         error: Option<&str>,
     ) -> Result<Vec<StreamResponse>, Error> {
-        println!("Following advice from server");
+        debug!(
+            "Following advice {:?} of the server, error: {:?}",
+            advice.reconnect, error
+        );
+        if advice.reconnect != Reconnect::None {
+            self.wait_interval(advice);
+        }
         match advice.reconnect {
             Reconnect::Handshake => {
                 if self.actual_retries <= self.max_retries {
+                    warn!(
+                        "Retrying /meta/handshake, retry {} of {}, error: {:?}",
+                        self.actual_retries, self.max_retries, error
+                    );
                     match self.retry_handshake() {
                         Ok(_) => {
                             self.subscribe()?;
@@ -157,6 +241,10 @@ impl CometdClient {
             }
             Reconnect::Retry => {
                 if self.actual_retries <= self.max_retries {
+                    warn!(
+                        "Retrying /meta/connect, retry {} of {}, error: {:?}",
+                        self.actual_retries, self.max_retries, error
+                    );
                     self.retry()
                 } else {
                     Err(Error::GenericError(
@@ -172,6 +260,14 @@ impl CometdClient {
         }
     }
 
+    /// Waits for the advised interval before reconnecting, which is how the
+    /// server paces its clients
+    fn wait_interval(&self, advice: &Advice) {
+        if let Some(interval) = advice.interval.filter(|interval| *interval > 0) {
+            thread::sleep(Duration::from_millis(interval.into()));
+        }
+    }
+
     /// Handles the error returned by the cometd server. If possible, it will
     /// automatically retry according to the client configuration. If it still
     /// fails after the retries, the original error will be returned.
@@ -188,48 +284,57 @@ impl CometdClient {
         }
     }
 
+    fn parse_response(response: Response) -> Result<Vec<StreamResponse>, Error> {
+        parse_json(response)
+    }
+
     fn handle_response(&mut self, response: Response) -> Result<Vec<StreamResponse>, Error> {
-        match response.into_json::<Vec<StreamResponse>>() {
-            Ok(stream_responses) => {
-                let mut responses = vec![];
-                for stream_response in stream_responses.into_iter() {
-                    match stream_response {
-                        StreamResponse::ErroredResponse(error_responses) => {
-                            let stream_responses = self.handle_error(&error_responses)?;
-
-                            for stream_response in stream_responses.into_iter() {
-                                responses.push(stream_response);
-                            }
-                        }
-                        StreamResponse::Handshake(handshake_response) => {
-                            self.stream_client_id = Some(handshake_response.client_id.clone());
-                            responses.push(StreamResponse::Handshake(handshake_response));
-                        }
-                        StreamResponse::Delivery(delivery_response) => {
-                            self.subscriptions.insert(
-                                delivery_response.channel.clone(),
-                                delivery_response.data.event.replay_id,
-                            );
-                            responses.push(StreamResponse::Delivery(delivery_response));
-                        }
+        let stream_responses = Self::parse_response(response)?;
+        self.handle_stream_responses(stream_responses)
+    }
 
-                        // Publish | Basic
-                        _ => {
-                            if let Some(ref advice) = stream_response.advice() {
-                                for stream_response in self.handle_advice(advice, None)? {
-                                    responses.push(stream_response);
-                                }
-                            }
+    fn handle_stream_responses(
+        &mut self,
+        stream_responses: Vec<StreamResponse>,
+    ) -> Result<Vec<StreamResponse>, Error> {
+        let mut responses = vec![];
+        for stream_response in stream_responses.into_iter() {
+            let advised_timeout = stream_response.advice().and_then(|advice| advice.timeout);
+            if let Some(advised_timeout) = advised_timeout {
+                self.advised_timeout = Some(Duration::from_millis(advised_timeout.into()));
+            }
+
+            match stream_response {
+                StreamResponse::ErroredResponse(error_responses) => {
+                    let stream_responses = self.handle_error(&error_responses)?;
+
+                    for stream_response in stream_responses.into_iter() {
+                        responses.push(stream_response);
+                    }
+                }
+                StreamResponse::Handshake(handshake_response) => {
+                    self.stream_client_id = Some(handshake_response.client_id.clone());
+                    responses.push(StreamResponse::Handshake(handshake_response));
+                }
+                StreamResponse::Delivery(delivery_response) => {
+                    self.subscriptions.insert(
+                        delivery_response.channel.clone(),
+                        delivery_response.data.event.replay_id,
+                    );
+                    responses.push(StreamResponse::Delivery(delivery_response));
+                }
+
+                // Publish | Basic
+                _ => {
+                    if let Some(ref advice) = stream_response.advice() {
+                        for stream_response in self.handle_advice(advice, None)? {
+                            responses.push(stream_response);
                         }
                     }
                 }
-                Ok(responses)
             }
-            Err(e) => Err(Error::GenericError(format!(
-                "Could not parse response: {:#?}",
-                e
-            ))),
         }
+        Ok(responses)
     }
 
     fn handshake(&mut self) -> Result<Vec<StreamResponse>, Error> {
@@ -257,6 +362,7 @@ impl CometdClient {
 
         if resps.is_ok() {
             self.actual_retries = 0;
+            self.last_connect_at = Some(Instant::now());
         }
         resps
     }
@@ -308,21 +414,24 @@ impl CometdClient {
     /// The cometd server's response could not be parsed.
     /// The cometd server returned a response that indicated an error and the request could not be
     /// retried or the maximum number of retries has been reached.
+    /// [ReplayIdExpired](Error::ReplayIdExpired) is returned when the replay id
+    /// of a subscription is no longer retained, unless
+    /// [set_replay_fallback](CometdClient::set_replay_fallback) is enabled.
     pub fn subscribe(&mut self) -> Result<(), Error> {
         match self.stream_client_id.clone() {
             Some(client_id) => {
                 for (subscription, replay_id) in self.subscriptions.clone() {
-                    println!("Subscribing with replay id {}", replay_id);
-                    let response = self.send_request(&SubscribeTopicPayload {
-                        channel: "/meta/subscribe",
-                        client_id: &client_id,
-                        subscription: &subscription,
-                        ext: Some(ExtReplay {
-                            replay: HashMap::from([(subscription.clone(), replay_id)]),
-                        }),
-                    })?;
-
-                    self.handle_response(response)?;
+                    match self.subscribe_channel(&client_id, &subscription, replay_id) {
+                        Err(Error::ReplayIdExpired { .. }) if self.replay_fallback => {
+                            warn!(
+                                "Replay id {} of {} expired, falling back to -1",
+                                replay_id, subscription
+                            );
+                            self.subscriptions.insert(subscription.clone(), -1);
+                            self.subscribe_channel(&client_id, &subscription, -1)?;
+                        }
+                        result => result?,
+                    }
                 }
 
                 Ok(())
@@ -333,6 +442,41 @@ impl CometdClient {
         }
     }
 
+    fn subscribe_channel(
+        &mut self,
+        client_id: &str,
+        subscription: &str,
+        replay_id: i64,
+    ) -> Result<(), Error> {
+        debug!(
+            "Subscribing to {} with replay id {}",
+            subscription, replay_id
+        );
+        let response = self.send_request(&SubscribeTopicPayload {
+            channel: "/meta/subscribe",
+            client_id,
+            subscription,
+            ext: Some(ExtReplay {
+                replay: HashMap::from([(subscription.to_string(), replay_id)]),
+            }),
+        })?;
+
+        let stream_responses = Self::parse_response(response)?;
+        let replay_id_expired = stream_responses.iter().any(|stream_response| {
+            matches!(stream_response, StreamResponse::ErroredResponse(errored_response)
+                if is_replay_id_error(&errored_response.error))
+        });
+        if replay_id_expired {
+            return Err(Error::ReplayIdExpired {
+                channel: subscription.to_string(),
+                replay_id,
+            });
+        }
+
+        self.handle_stream_responses(stream_responses)?;
+        Ok(())
+    }
+
     /// The cometd subscribe method. It will ask the server to unsubscribe from a certain channel and therefore
     /// strop being updated when something is posted on this channel.
     /// If one or several sucess responses are returned to the request, it will return a `Vec`
@@ -380,6 +524,27 @@ impl CometdClient {
         &mut self,
         channel: &str,
         data: impl Serialize + std::fmt::Debug,
+    ) -> Result<Vec<StreamResponse>, Error> {
+        self.publish_message(channel, None, data)
+    }
+
+    /// Same as [publish](CometdClient::publish), setting the Bayeux message
+    /// `id`. The server echoes it in its reply, so that the reply can be
+    /// correlated to the message with [StreamResponse::id](StreamResponse::id)
+    pub fn publish_with_id(
+        &mut self,
+        channel: &str,
+        id: &str,
+        data: impl Serialize + std::fmt::Debug,
+    ) -> Result<Vec<StreamResponse>, Error> {
+        self.publish_message(channel, Some(id), data)
+    }
+
+    fn publish_message(
+        &mut self,
+        channel: &str,
+        id: Option<&str>,
+        data: impl Serialize + std::fmt::Debug,
     ) -> Result<Vec<StreamResponse>, Error> {
         match &self.stream_client_id {
             Some(client_id) => {
@@ -387,24 +552,48 @@ impl CometdClient {
                     channel,
                     client_id,
                     data,
+                    id,
                 })?;
 
-                self.handle_response(response)
+                // The acknowledgements of the message are kept, so that they
+                // can be correlated by id
+                let (mut acks, stream_responses): (Vec<_>, Vec<_>) =
+                    Self::parse_response(response)?
+                        .into_iter()
+                        .partition(|stream_response| {
+                            stream_response.advice().is_none()
+                                && matches!(
+                                    stream_response,
+                                    StreamResponse::Publish(_) | StreamResponse::Basic(_)
+                                )
+                        });
+                let mut responses = self.handle_stream_responses(stream_responses)?;
+                responses.append(&mut acks);
+                Ok(responses)
             }
             None => Err(Error::GenericError(
-                "No client id set for unsubscribe".to_string(),
+                "No client id set for publish".to_string(),
             )),
         }
     }
 }
 
+/// Tells whether a subscribe error is caused by a replay id which is unknown
+/// to the server, e.g. `400::The replayId {12} you provided was invalid.`
+fn is_replay_id_error(error: &str) -> bool {
+    error.contains("replayId") && error.contains("invalid")
+}
+
 #[cfg(test)]
 mod tests {
+    use log::Level;
     use mockito::Server as MockServer;
     use serde_json::json;
     use std::collections::HashMap;
+    use std::time::{Duration, Instant};
 
     use super::CometdClient;
+    use crate::test_utils::captured_logs;
     use crate::Client;
 
     static RETRIES_MAX: i8 = 3;
@@ -464,6 +653,100 @@ mod tests {
         }
     }
 
+    mod subscribe {
+        use super::*;
+        use crate::errors::Error;
+
+        fn mock_expired_replay(server: &mut MockServer) -> mockito::Mock {
+            server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(
+                    r#"{"channel":"/meta/handshake","version":"1.0","supportedConnectionTypes":["long-polling"]}"#,
+                )
+                .with_body(
+                    json!([{
+                        "channel": "/meta/handshake",
+                        "version": "1.0",
+                        "successful": true,
+                        "clientId": "1234",
+                        "supportedConnectionTypes": ["long-polling"]
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(mockito::Matcher::PartialJson(json!({
+                    "channel": "/meta/subscribe",
+                    "ext": { "replay": { "/data/AccountChangeEvent": 12 } }
+                })))
+                .with_body(
+                    json!([{
+                        "channel": "/meta/subscribe",
+                        "clientId": "1234",
+                        "subscription": "/data/AccountChangeEvent",
+                        "error": "400::The replayId {12} you provided was invalid.  Please provide a valid ID, -2 to replay all events, or -1 to replay only new events.",
+                        "successful": false
+                    }])
+                    .to_string(),
+                )
+                .create()
+        }
+
+        #[test]
+        fn returns_replay_id_expired() {
+            let mut server = MockServer::new_with_port(0);
+            let _m = mock_expired_replay(&mut server);
+            let mut client = client(&server);
+            client
+                .subscriptions
+                .insert("/data/AccountChangeEvent".to_string(), 12);
+
+            let err = client.init().expect_err("Init should not return Ok");
+            assert!(matches!(
+                err,
+                Error::ReplayIdExpired { channel, replay_id: 12 } if channel == "/data/AccountChangeEvent"
+            ));
+        }
+
+        #[test]
+        fn falls_back_to_new_events() {
+            let mut server = MockServer::new_with_port(0);
+            let _m = mock_expired_replay(&mut server);
+            let fallback_mock = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(mockito::Matcher::PartialJson(json!({
+                    "channel": "/meta/subscribe",
+                    "ext": { "replay": { "/data/AccountChangeEvent": -1 } }
+                })))
+                .with_body(
+                    json!([{
+                        "channel": "/meta/subscribe",
+                        "clientId": "1234",
+                        "subscription": "/data/AccountChangeEvent",
+                        "successful": true
+                    }])
+                    .to_string(),
+                )
+                .create();
+            let mut client = client(&server).set_replay_fallback(true);
+            client
+                .subscriptions
+                .insert("/data/AccountChangeEvent".to_string(), 12);
+
+            client.init().expect("Could not init client");
+            fallback_mock.assert();
+            assert_eq!(
+                Some(&-1),
+                client.subscriptions.get("/data/AccountChangeEvent")
+            );
+        }
+    }
+
     mod connect {
         use super::*;
 
@@ -508,11 +791,214 @@ mod tests {
                 .expect(RETRIES_MAX as usize + 1)
                 .create();
 
+            captured_logs();
             let mut client = client(&server);
 
             client.init().expect("Could not init client");
             client.connect().expect_err("Connect should not return Ok");
             connect_mock.assert();
+            let retries = captured_logs()
+                .into_iter()
+                .filter(|(_, message)| message.starts_with("Retrying /meta/connect"))
+                .collect::<Vec<_>>();
+            assert_eq!(RETRIES_MAX as usize, retries.len());
+            assert!(retries.iter().all(|(level, _)| *level == Level::Warn));
+            assert_eq!(
+                "Retrying /meta/connect, retry 1 of 3, error: Some(\"400::Error\")",
+                retries[0].1
+            );
+        }
+
+        #[test]
+        fn tracks_replay_ids() {
+            let mut server = MockServer::new_with_port(0);
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(
+                    r#"{"channel":"/meta/handshake","version":"1.0","supportedConnectionTypes":["long-polling"]}"#,
+                )
+                .with_body(
+                    json!([{
+                        "channel": "/meta/handshake",
+                        "version": "1.0",
+                        "successful": true,
+                        "clientId": "1234",
+                        "supportedConnectionTypes": ["long-polling"]
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(mockito::Matcher::PartialJson(json!({
+                    "channel": "/meta/subscribe",
+                    "ext": { "replay": { "/data/AccountChangeEvent": 41 } }
+                })))
+                .with_body(
+                    json!([{
+                        "channel": "/meta/subscribe",
+                        "clientId": "1234",
+                        "subscription": "/data/AccountChangeEvent",
+                        "successful": true
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(
+                    r#"{"channel":"/meta/connect","clientId":"1234","connectionType":"long-polling"}"#,
+                )
+                .with_body(
+                    json!([{
+                        "channel": "/data/AccountChangeEvent",
+                        "data": {
+                            "event": { "replayId": 42 },
+                            "payload": {}
+                        }
+                    }, {
+                        "channel": "/meta/connect",
+                        "successful": true
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let mut client = CometdClient::new(
+                client(&server).client,
+                HashMap::from([("/data/AccountChangeEvent".to_string(), 41)]),
+            );
+
+            client.init().expect("Could not init client");
+            client.connect().expect("Connect should return Ok");
+            assert_eq!(
+                HashMap::from([("/data/AccountChangeEvent".to_string(), 42)]),
+                client.replay_ids()
+            );
+        }
+
+        #[test]
+        fn tracks_last_connect_on_heartbeat() {
+            let mut server = MockServer::new_with_port(0);
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(
+                    r#"{"channel":"/meta/handshake","version":"1.0","supportedConnectionTypes":["long-polling"]}"#,
+                )
+                .with_body(
+                    json!([{
+                        "channel": "/meta/handshake",
+                        "version": "1.0",
+                        "successful": true,
+                        "clientId": "1234",
+                        "supportedConnectionTypes": ["long-polling"]
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(
+                    r#"{"channel":"/meta/connect","clientId":"1234","connectionType":"long-polling"}"#,
+                )
+                .with_body(
+                    json!([{
+                        "channel": "/meta/connect",
+                        "successful": true
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let mut client = client(&server);
+
+            client.init().expect("Could not init client");
+            assert!(client.last_connect_at().is_none());
+
+            let responses = client.connect().expect("Connect should return Ok");
+            assert!(responses.is_empty());
+            assert!(client.last_connect_at().is_some());
+        }
+
+        #[test]
+        fn waits_advised_interval_before_retrying() {
+            let mut server = MockServer::new_with_port(0);
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(
+                    r#"{"channel":"/meta/handshake","version":"1.0","supportedConnectionTypes":["long-polling"]}"#,
+                )
+                .with_body(
+                    json!([{
+                        "channel": "/meta/handshake",
+                        "version": "1.0",
+                        "successful": true,
+                        "clientId": "1234",
+                        "supportedConnectionTypes": ["long-polling"]
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(
+                    r#"{"channel":"/meta/connect","clientId":"1234","connectionType":"long-polling"}"#,
+                )
+                .with_body(
+                    json!([{
+                        "advice": { "reconnect": "retry", "interval": 25 },
+                        "channel": "/meta/connect",
+                        "error": "400::Error",
+                        "successful": false
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let mut client = client(&server).set_retries(1);
+            client.init().expect("Could not init client");
+
+            let started_at = Instant::now();
+            client.connect().expect_err("Connect should not return Ok");
+            // one wait per retry advice: the retry and the last attempt
+            assert!(started_at.elapsed() >= Duration::from_millis(50));
+        }
+
+        #[test]
+        fn honors_advised_timeout() {
+            let mut server = MockServer::new_with_port(0);
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .with_body(
+                    json!([{
+                        "channel": "/meta/handshake",
+                        "version": "1.0",
+                        "successful": true,
+                        "clientId": "1234",
+                        "supportedConnectionTypes": ["long-polling"],
+                        "advice": { "reconnect": "none", "timeout": 200000 }
+                    }])
+                    .to_string(),
+                )
+                .create();
+
+            let mut client = client(&server).set_timeout(Duration::from_secs(30));
+            assert_eq!(Duration::from_secs(30), client.connect_timeout());
+
+            client.init().expect("Could not init client");
+            assert_eq!(Duration::from_secs(210), client.connect_timeout());
         }
 
         #[test]
@@ -564,4 +1050,48 @@ mod tests {
             hs_mock.assert();
         }
     }
+
+    mod publish {
+        use super::*;
+
+        #[test]
+        fn echoes_message_id() {
+            let mut server = MockServer::new_with_port(0);
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(mockito::Matcher::PartialJson(
+                    json!({ "channel": "/meta/handshake" }),
+                ))
+                .with_body(
+                    json!([{
+                        "channel": "/meta/handshake",
+                        "version": "1.0",
+                        "successful": true,
+                        "clientId": "1234",
+                        "supportedConnectionTypes": ["long-polling"]
+                    }])
+                    .to_string(),
+                )
+                .create();
+            let _m = server
+                .mock("POST", "/cometd/56.0")
+                .with_status(200)
+                .match_body(mockito::Matcher::PartialJson(json!({
+                    "channel": "/u/rpc",
+                    "id": "42"
+                })))
+                .with_body(
+                    json!([{ "channel": "/u/rpc", "successful": true, "id": "42" }]).to_string(),
+                )
+                .create();
+
+            let mut client = client(&server);
+            client.init().expect("Could not init client");
+            let responses = client
+                .publish_with_id("/u/rpc", "42", json!({ "x": 1 }))
+                .expect("Could not publish");
+            assert_eq!(Some("42"), responses[0].id());
+        }
+    }
 }
@@ -113,7 +113,18 @@ impl StreamResponse {
             StreamResponse::Publish(resp) => resp.advice.clone(),
             StreamResponse::Delivery(resp) => resp.advice.clone(),
             StreamResponse::Basic(resp) => resp.advice.clone(),
-            _ => None,
+            StreamResponse::ErroredResponse(resp) => resp.advice.clone(),
+        }
+    }
+
+    /// Returns the `id` of the message this response replies to, if any
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            StreamResponse::Handshake(resp) => resp.id.as_deref(),
+            StreamResponse::Publish(resp) => resp.id.as_deref(),
+            StreamResponse::Delivery(resp) => resp.id.as_deref(),
+            StreamResponse::Basic(resp) => resp.id.as_deref(),
+            StreamResponse::ErroredResponse(resp) => resp.id.as_deref(),
         }
     }
 }
@@ -0,0 +1,49 @@
+//! Helpers shared by the tests of several modules
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::Mutex;
+use std::thread;
+
+/// Collects the log records of the crate with the name of the thread which
+/// emitted them, i.e. the name of the test
+struct CapturingLogger {
+    records: Mutex<Vec<(Option<String>, Level, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        self.records.lock().unwrap().push((
+            thread::current().name().map(String::from),
+            record.level(),
+            record.args().to_string(),
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    records: Mutex::new(Vec::new()),
+};
+
+/// Returns the records logged so far by the current test
+pub(crate) fn captured_logs() -> Vec<(Level, String)> {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Trace);
+    let name = thread::current().name().map(String::from);
+    LOGGER
+        .records
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(thread, _, _)| *thread == name)
+        .map(|(_, level, message)| (*level, message.clone()))
+        .collect()
+}
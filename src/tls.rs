@@ -0,0 +1,82 @@
+use rustls::{Certificate, ClientConfig, OwnedTrustAnchor, RootCertStore};
+use rustls_pemfile::Item;
+use std::sync::Arc;
+
+use crate::errors::Error;
+
+/// Reads the certificates of a PEM file, e.g. the CA of an SSL-inspecting
+/// gateway, see [add_root_certificate](crate::client::Client::add_root_certificate)
+pub(crate) fn read_certificates(pem: &[u8]) -> Result<Vec<Certificate>, Error> {
+    let invalid =
+        |reason: &str| Error::GenericError(format!("Invalid root certificate: {}", reason));
+
+    let certs: Vec<Certificate> = rustls_pemfile::read_all(&mut &pem[..])
+        .map_err(|e| invalid(&e.to_string()))?
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::X509Certificate(der) => Some(Certificate(der)),
+            _ => None,
+        })
+        .collect();
+    if certs.is_empty() {
+        return Err(invalid("no certificate found"));
+    }
+    Ok(certs)
+}
+
+/// The Mozilla root certificates, which the agent trusts by default, along
+/// with `extra` ones
+pub(crate) fn root_store(extra: &[Certificate]) -> Result<RootCertStore, Error> {
+    let mut roots = RootCertStore::empty();
+    roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            ta.subject,
+            ta.spki,
+            ta.name_constraints,
+        )
+    }));
+    for cert in extra {
+        roots
+            .add(cert)
+            .map_err(|e| Error::GenericError(format!("Invalid root certificate: {:?}", e)))?;
+    }
+    Ok(roots)
+}
+
+/// Builds the TLS configuration trusting `roots`, without client certificate
+pub(crate) fn client_config(roots: RootCertStore) -> Arc<ClientConfig> {
+    Arc::new(
+        ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_certificates, root_store};
+    use std::fs;
+
+    #[test]
+    fn reads_certificates() {
+        let cert = fs::read("tests/fixtures/jwt_cert.pem").unwrap();
+        let certs = read_certificates(&cert).unwrap();
+        assert_eq!(1, certs.len());
+        assert!(root_store(&certs).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_pem() {
+        let key = fs::read("tests/fixtures/jwt_pkcs8.pem").unwrap();
+        let err = read_certificates(&key).err().unwrap();
+        assert!(err.to_string().contains("no certificate found"));
+
+        let err = read_certificates(b"not a certificate").err().unwrap();
+        assert!(err.to_string().contains("no certificate found"));
+
+        let garbage = b"-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n";
+        let certs = read_certificates(garbage).unwrap();
+        assert!(root_store(&certs).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! The HTTP layer of a [Client](crate::client::Client), which can be replaced
+//! with [with_transport](crate::client::Client::with_transport), e.g. by a
+//! [MockTransport](MockTransport) to unit test code using a client without a
+//! server
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// A request sent through a [Transport](Transport). The URL includes the
+/// query parameters
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransportRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl TransportRequest {
+    /// Returns the value of a header, case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the body read as UTF-8, e.g. to check a JSON body
+    pub fn body_string(&self) -> Option<String> {
+        self.body
+            .as_ref()
+            .map(|body| String::from_utf8_lossy(body).to_string())
+    }
+
+    pub(crate) fn from_ureq(
+        req: &ureq::Request,
+        body: Option<&[u8]>,
+    ) -> Result<Self, Box<ureq::Error>> {
+        Ok(TransportRequest {
+            method: req.method().to_string(),
+            url: req.request_url()?.as_url().to_string(),
+            headers: req
+                .header_names()
+                .into_iter()
+                .filter_map(|name| {
+                    let value = req.header(&name)?.to_string();
+                    Some((name, value))
+                })
+                .collect(),
+            body: body.map(|body| body.to_vec()),
+        })
+    }
+}
+
+/// A response returned by a [Transport](Transport). Error statuses are
+/// responses as well, not errors
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl TransportResponse {
+    /// A response with a JSON body
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        TransportResponse {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    /// Converts into the response of the agent, failing with
+    /// [Status](ureq::Error::Status) for statuses from 400, the way the agent
+    /// does
+    pub(crate) fn into_response(self) -> Result<ureq::Response, Box<ureq::Error>> {
+        let mut raw = format!("HTTP/1.1 {} Status\r\n", self.status);
+        for (name, value) in &self.headers {
+            let _ = write!(raw, "{}: {}\r\n", name, value);
+        }
+        raw.push_str("\r\n");
+        raw.push_str(&self.body);
+        let res: ureq::Response = raw.parse()?;
+        if res.status() >= 400 {
+            return Err(Box::new(ureq::Error::Status(res.status(), res)));
+        }
+        Ok(res)
+    }
+}
+
+/// Sends the requests of a [Client](crate::client::Client). Only network
+/// failures are errors: a response with an error status is returned as a
+/// [TransportResponse](TransportResponse)
+pub trait Transport: Send + Sync {
+    fn send(&self, request: TransportRequest) -> io::Result<TransportResponse>;
+}
+
+impl Transport for ureq::Agent {
+    fn send(&self, request: TransportRequest) -> io::Result<TransportResponse> {
+        let req = request.headers.iter().fold(
+            self.request(&request.method, &request.url),
+            |req, (name, value)| req.set(name, value),
+        );
+        let res = match request.body.as_deref() {
+            Some(body) => req.send_bytes(body),
+            None => req.call(),
+        };
+        let res = match res {
+            Ok(res) | Err(ureq::Error::Status(_, res)) => res,
+            Err(error) => return Err(io::Error::other(error)),
+        };
+        let status = res.status();
+        let headers = res
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = res.header(&name)?.to_string();
+                Some((name, value))
+            })
+            .collect();
+        Ok(TransportResponse {
+            status,
+            headers,
+            body: res.into_string()?,
+        })
+    }
+}
+
+/// A [Transport](Transport) returning queued responses in order and recording
+/// the requests. Clones share the queue and the recorded requests, so a clone
+/// can be kept to check the requests after passing the transport to a client
+///
+/// ```rust
+/// use rust_sync_force::transport::{MockTransport, TransportResponse};
+/// use rust_sync_force::Client;
+/// use serde_json::json;
+///
+/// let transport = MockTransport::new();
+/// transport.push(TransportResponse::json(
+///     201,
+///     json!({ "id": "001", "success": true, "errors": [] }),
+/// ));
+/// let mut client = Client::new(None, None).with_transport(Box::new(transport.clone()));
+/// client.set_instance_url("https://acme.my.salesforce.com");
+/// client.set_access_token("00D...");
+///
+/// let res = client.insert("Account", [("Name", "Acme")]).unwrap();
+/// assert_eq!("001", res.id);
+/// assert_eq!("POST", transport.requests()[0].method);
+/// ```
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    responses: Arc<Mutex<VecDeque<TransportResponse>>>,
+    requests: Arc<Mutex<Vec<TransportRequest>>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Queues the response of the next request
+    pub fn push(&self, response: TransportResponse) -> &Self {
+        self.responses.lock().unwrap().push_back(response);
+        self
+    }
+
+    /// Queues a JSON response, see [TransportResponse::json](TransportResponse::json)
+    pub fn push_json(&self, status: u16, body: serde_json::Value) -> &Self {
+        self.push(TransportResponse::json(status, body))
+    }
+
+    /// Returns the requests sent so far
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: TransportRequest) -> io::Result<TransportResponse> {
+        let error = format!("No response queued for {} {}", request.method, request.url);
+        self.requests.lock().unwrap().push(request);
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, error))
+    }
+}
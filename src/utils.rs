@@ -1,6 +1,78 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+
+use crate::errors::{truncate, Error, ERROR_BODY_MAX_LEN};
+
+/// Characters left untouched by [url_encode](url_encode), as per RFC 3986
+const URL_UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Reads the body of a response as JSON, whatever its size, keeping the start
+/// of the raw body in the error when it does not match the expected type
+pub(crate) fn parse_json<T: DeserializeOwned>(res: ureq::Response) -> Result<T, Error> {
+    let mut body = Vec::new();
+    res.into_reader().read_to_end(&mut body)?;
+    from_json_slice(&body)
+}
+
+/// Deserializes a JSON body, see [parse_json](parse_json)
+pub(crate) fn from_json_slice<T: DeserializeOwned>(body: &[u8]) -> Result<T, Error> {
+    serde_json::from_slice(body).map_err(|source| {
+        let start = &body[..body.len().min(ERROR_BODY_MAX_LEN)];
+        Error::DeserializeError {
+            body: truncate(String::from_utf8_lossy(start).into_owned()),
+            source,
+        }
+    })
+}
+
 pub fn substring_before(body: &str, separator: &str) -> String {
     match body.find(separator) {
         Some(i) => body.get(..i).unwrap().to_string(),
         None => body.to_string(),
     }
 }
+
+pub fn substring_after(body: &str, separator: &str) -> String {
+    match body.find(separator) {
+        Some(i) => body.get(i + separator.len()..).unwrap().to_string(),
+        None => body.to_string(),
+    }
+}
+
+/// Decodes a percent-encoded string (e.g. `%2B` into `+`)
+pub fn url_decode(value: &str) -> Result<String, Error> {
+    percent_decode_str(value)
+        .decode_utf8()
+        .map(|decoded| decoded.into_owned())
+        .map_err(|e| Error::GenericError(format!("Invalid percent-encoded value: {}", e)))
+}
+
+/// Percent-encodes a value so it can be used as a query parameter
+pub fn url_encode(value: &str) -> String {
+    utf8_percent_encode(value, URL_UNRESERVED).to_string()
+}
+
+/// Generates a PKCE `(code_verifier, code_challenge)` pair for the OAuth2 web
+/// server flow. The challenge uses the `S256` method
+pub fn generate_pkce_pair() -> Result<(String, String), Error> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| Error::GenericError(format!("Could not generate PKCE verifier: {}", e)))?;
+    let verifier = URL_SAFE_NO_PAD.encode(bytes);
+    let challenge = pkce_challenge(&verifier);
+    Ok((verifier, challenge))
+}
+
+/// Computes the `S256` PKCE code challenge of a code verifier
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}